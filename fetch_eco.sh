@@ -0,0 +1,15 @@
+#!/bin/bash
+set -e
+
+# Baixa os volumes a.tsv–e.tsv do lichess-org/chess-openings (CC0) sem
+# modificações para data/eco, onde são embutidos no binário (src/eco.rs)
+BASE="https://raw.githubusercontent.com/lichess-org/chess-openings/master"
+DEST="data/eco"
+
+mkdir -p "$DEST"
+for vol in a b c d e; do
+    curl -fsSL "$BASE/$vol.tsv" -o "$DEST/$vol.tsv"
+done
+
+echo "Tabela ECO atualizada em $DEST ($(cat "$DEST"/*.tsv | wc -l) linhas)."
+exit 0
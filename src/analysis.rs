@@ -9,7 +9,7 @@
 //  * Fail‑fast, sem funções auxiliares redundantes
 // ---------------------------------------------------------------------------
 use anyhow::Result;
-use shakmaty::{Chess, Color, Move};
+use shakmaty::{Chess, Color, Move, Position};
 use crate::{
     config,
     engine::{AnalysisInfo, Engine},
@@ -22,9 +22,12 @@ use ruci::engine::ScoreStandardized;
 pub struct SolverResponse {
     pub solution_move:     Move,
     pub alternative_moves: Vec<Move>,
+    pub alternative_lines: Vec<Vec<Move>>,   // PV completa de cada alternativa equivalente
     pub ambiguous:         bool,
     pub score:             ScoreStandardized,
     pub post_cp:           i32,
+    pub second_cp:         Option<i32>,   // melhor lance fora do cluster (ponto de vista das brancas)
+    pub wrong_lines:       Vec<(Vec<Move>, i32)>, // lances fora do cluster: PV (refutação) e cp
 }
 
 /// Analisa, clusteriza e gera SolverResponse.
@@ -67,11 +70,12 @@ pub async fn solver_response(
 
     // Agrupa lances similares dentro do threshold definido
     // Isso captura variações equivalentes para a mesma tática
-    let cluster: Vec<Move> = ordered
+    let lines: Vec<&Vec<Move>> = ordered
         .iter()
         .take_while(|i| Engine::key_diff(base, i.score.as_ref().unwrap()) <= thr)
-        .filter_map(|i| i.pv.first().cloned())
+        .map(|i| &i.pv)
         .collect();
+    let cluster: Vec<Move> = lines.iter().filter_map(|pv| pv.first().cloned()).collect();
     if cluster.is_empty() { return Ok(None); }
 
     // Verifica se há ambiguidade: se o próximo melhor lance fora do cluster
@@ -82,12 +86,21 @@ pub async fn solver_response(
                ordered[cluster.len()].score.as_ref().unwrap(),
            ) < config::PUZZLE_UNICITY_THRESHOLD as i64;
 
+    let second_cp   = ordered.get(cluster.len()).and_then(|i| i.score.as_ref()).map(Engine::to_cp);
+    let wrong_lines = ordered[cluster.len()..]
+        .iter()
+        .filter_map(|i| Some((i.pv.clone(), Engine::to_cp(i.score.as_ref()?))))
+        .collect();
+
     Ok(Some(SolverResponse {
         solution_move:     cluster[0].clone(),
         alternative_moves: cluster.into_iter().skip(1).collect(),
+        alternative_lines: lines.into_iter().skip(1).cloned().collect(),
         ambiguous,
-        score:   base.clone(),
-        post_cp: Engine::to_cp(base),
+        score:     base.clone(),
+        post_cp:   Engine::to_cp(base),
+        second_cp,
+        wrong_lines,
     }))
 }
 
@@ -122,3 +135,25 @@ pub async fn puzzle_is_interesting(
         || (pre_cp > 0 && second_cp < -config::DRAWING_RANGE)
         || (pre_cp < 0 && second_cp >  config::DRAWING_RANGE))
 }
+
+/// Confirma que o blunder era evitável: algum outro lance da posição anterior
+/// fica ao menos `BLUNDER_THRESHOLD` melhor (do ponto de vista de quem errou)
+/// que a avaliação após o blunder. Devolve o melhor desses lances e seu cp.
+pub async fn avoidable_alternative(
+    engine:  &mut Engine,
+    pre:     &Chess,
+    blunder: &Move,
+    post_cp: i32,
+    depth:   u8,
+) -> Result<Option<(Move, i32)>> {
+    let sign  = if pre.turn() == Color::White { 1 } else { -1 };
+    let infos = engine.analyze(pre, depth, config::AVOIDABLE_MULTIPV).await?;
+
+    // `analyze` já ordena do melhor para o pior para o lado a mover
+    Ok(infos
+        .iter()
+        .filter_map(|i| Some((i.pv.first()?, Engine::to_cp(i.score.as_ref()?))))
+        .find(|(mv, _)| *mv != blunder)
+        .filter(|(_, cp)| sign * (cp - post_cp) >= config::BLUNDER_THRESHOLD)
+        .map(|(mv, cp)| (mv.clone(), cp)))
+}
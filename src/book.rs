@@ -0,0 +1,127 @@
+// src/book.rs
+// ---------------------------------------------------------------------------
+// Livro de aberturas Polyglot (.bin): entradas de 16 bytes big‑endian
+// (chave, lance, peso, learn) ordenadas pela chave. A chave Polyglot é o
+// Zobrist64 do shakmaty (`draws::position_key`).
+// ---------------------------------------------------------------------------
+
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use shakmaty::{Chess, Move, Position, Role, Square};
+
+use crate::draws::position_key;
+
+const ENTRY_BYTES: usize = 16;
+
+/// Entrada do livro: chave da posição, lance codificado e peso.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    key:    u64,
+    mv:     u16,
+    weight: u16,
+}
+
+pub struct Book {
+    entries: Vec<Entry>,   // ordenadas por chave, como no arquivo
+}
+
+impl Book {
+    pub fn open(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).with_context(|| format!("abrir livro {}", path.display()))?;
+        Self::from_bytes(&bytes).with_context(|| format!("livro Polyglot inválido: {}", path.display()))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if !bytes.len().is_multiple_of(ENTRY_BYTES) { bail!("tamanho não é múltiplo de {ENTRY_BYTES} bytes"); }
+        let mut entries: Vec<Entry> = bytes
+            .chunks_exact(ENTRY_BYTES)
+            .map(|c| Entry {
+                key:    u64::from_be_bytes(c[0..8].try_into().unwrap()),
+                mv:     u16::from_be_bytes([c[8], c[9]]),
+                weight: u16::from_be_bytes([c[10], c[11]]),
+            })
+            .collect();
+        entries.sort_by_key(|e| e.key);   // tolera arquivos fora de ordem
+        Ok(Self { entries })
+    }
+
+    /// Lances de livro da posição com seus pesos.
+    pub fn moves(&self, pos: &Chess) -> Vec<(Move, u16)> {
+        let key   = position_key(pos);
+        let start = self.entries.partition_point(|e| e.key < key);
+        self.entries[start..]
+            .iter()
+            .take_while(|e| e.key == key)
+            .filter_map(|e| Some((decode_move(pos, e.mv)?, e.weight)))
+            .collect()
+    }
+
+    /// O lance jogado ainda é teoria?
+    pub fn contains(&self, pos: &Chess, mv: &Move) -> bool {
+        self.moves(pos).iter().any(|(m, _)| m == mv)
+    }
+}
+
+/// Lance Polyglot (destino, origem, promoção) → lance legal da posição.
+/// Roque vem como rei captura a própria torre, igual a `Move::Castle`.
+fn decode_move(pos: &Chess, raw: u16) -> Option<Move> {
+    let to    = Square::new(u32::from(raw & 0x3f));
+    let from  = Square::new(u32::from((raw >> 6) & 0x3f));
+    let promo = match (raw >> 12) & 0x7 {
+        0 => None,
+        1 => Some(Role::Knight),
+        2 => Some(Role::Bishop),
+        3 => Some(Role::Rook),
+        4 => Some(Role::Queen),
+        _ => return None,
+    };
+    pos.legal_moves()
+        .into_iter()
+        .find(|m| m.from() == Some(from) && m.to() == to && m.promotion() == promo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shakmaty::{fen::Fen, CastlingMode, uci::UciMove};
+
+    fn entry(pos: &Chess, from: Square, to: Square, weight: u16) -> Vec<u8> {
+        let mv = (u16::from(from) << 6) | u16::from(to);
+        let mut bytes = position_key(pos).to_be_bytes().to_vec();
+        bytes.extend(mv.to_be_bytes());
+        bytes.extend(weight.to_be_bytes());
+        bytes.extend(0u32.to_be_bytes());
+        bytes
+    }
+
+    fn uci(pos: &Chess, s: &str) -> Move {
+        s.parse::<UciMove>().unwrap().to_move(pos).unwrap()
+    }
+
+    #[test]
+    fn start_position_key_matches_polyglot() {
+        assert_eq!(position_key(&Chess::default()), 0x463b_9618_1691_fc9c);
+    }
+
+    #[test]
+    fn book_moves_are_decoded() {
+        let start = Chess::default();
+        let mut bytes = entry(&start, Square::D2, Square::D4, 7);
+        bytes.extend(entry(&start, Square::E2, Square::E4, 10));
+        let book = Book::from_bytes(&bytes).unwrap();
+
+        assert!(book.contains(&start, &uci(&start, "e2e4")));
+        assert!(book.contains(&start, &uci(&start, "d2d4")));
+        assert!(!book.contains(&start, &uci(&start, "g2g4")));
+        assert_eq!(book.moves(&start).len(), 2);
+    }
+
+    #[test]
+    fn castling_is_king_takes_rook() {
+        let pos: Chess = "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1"
+            .parse::<Fen>().unwrap().into_position(CastlingMode::Standard).unwrap();
+        let book = Book::from_bytes(&entry(&pos, Square::E1, Square::H1, 1)).unwrap();
+        assert!(book.contains(&pos, &uci(&pos, "e1g1")));
+    }
+}
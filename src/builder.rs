@@ -6,26 +6,97 @@
 // ---------------------------------------------------------------------------
 
 use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use shakmaty_syzygy::Wdl;
+use futures_util::future::{FutureExt, LocalBoxFuture};
 use indexmap::IndexMap;
-use log::{debug, info, trace};
+use log::{debug, trace};
 use shakmaty::{
-    fen::Fen, CastlingSide, Chess, Color, Move, Position, Role, EnPassantMode, uci::UciMove,
+    fen::Fen, san::San, Bitboard, Chess, Color, Move, Position, Role, EnPassantMode, uci::UciMove,
 };
 
 use crate::{
     analysis::{solver_response, puzzle_is_interesting},
-    candidates::PuzzleCandidate,
+    candidates::{CandidateKind, PuzzleCandidate},
     config,
-    engine::Engine,
-    utils::{DepthSet, build_pgn_san},
+    difficulty::{estimate, SearchProfile},
+    draws::{DrawKind, RepetitionHistory},
+    eco,
+    endgame::{classify_endgame, material_signature, EndgameCategory},
+    engine::{AnalysisInfo, Engine},
+    exporter::{ExportOptions, RefutationExport},
+    human::HumanCheck,
+    mates::{classify_mate, mate_length_tag},
+    material::{detect_sacrifice, material_balance, material_track},
+    motifs::{detect_motifs, detect_move_tags, detect_sequence_motifs, Motif, MoveTag},
+    see::{trivial_first_move, TrivialKind},
+    statistics::PuzzleStatistics,
+    utils::{DepthSet, build_pgn_san, replay_line, san_line, san_moves},
 };
 
 #[derive(Debug, Clone)]
 pub struct PuzzleSeq {
     pub moves:        Vec<Move>,
-    pub alternatives: Vec<Vec<Move>>,
+    pub unique:       Vec<bool>,              // por lance do resolvedor: sem lances equivalentes
+    pub variations:   Vec<Variation>,         // defesas alternativas do adversário
     pub final_cp:     i32,
     pub is_mate:      bool,
+    pub comments:     Vec<(usize, String)>,   // (índice do lance, comentário após ele)
+    pub evals:        Vec<Option<i32>>,       // cp (brancas) após cada lance, se conhecido
+    pub glyphs:       Vec<(usize, &'static str)>, // sufixos "!", "??" por índice do lance
+    pub refutations:  Vec<Refutation>,
+    pub draw:         Option<DrawKind>,       // linha termina empatada (puzzle de salvação)
+}
+
+/// Lance errado plausível num nó do resolvedor e a linha que o refuta.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Refutation {
+    pub ply:  usize,          // índice do lance do resolvedor que ele substitui
+    pub line: Vec<Move>,      // lance errado + refutação
+    pub cp:   i32,            // avaliação (brancas) após o lance errado
+}
+
+/// Variante que substitui `moves[ply]` da linha-mãe; aninhável.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variation {
+    pub ply:        usize,
+    pub moves:      Vec<Move>,
+    pub variations: Vec<Variation>,           // índices relativos a `moves`
+}
+
+impl Variation {
+    /// Mesma variante com o ponto de ramificação deslocado em `n` plies.
+    pub fn shifted(&self, n: usize) -> Self { Self { ply: self.ply + n, ..self.clone() } }
+}
+
+/// Limites da árvore de solução e escolha da defesa da linha principal.
+#[derive(Debug, Clone, Copy)]
+pub struct TreeOptions {
+    pub branching: usize,           // defesas extras exploradas em cada nó do adversário
+    pub max_nodes: usize,           // lances na árvore inteira (linha principal + variantes)
+    pub defense:   DefensePolicy,
+    pub mistakes:  MistakePolicy,
+}
+
+/// Qual defesa do adversário segue na linha principal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DefensePolicy {
+    /// Melhor lance do motor
+    Best,
+    /// Defesa que deixa o resolvedor com a menor avaliação após a melhor resposta
+    LongestResistance,
+    /// Defesa após a qual o resolvedor tem menos lances vencedores
+    Trickiest,
+}
+
+/// Quais lances errados do resolvedor contam como "plausíveis" (refutações).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MistakePolicy {
+    /// Ordem do motor: os erros mais bem avaliados primeiro
+    Engine,
+    /// Xeques e capturas primeiro, depois a ordem do motor
+    Forcing,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -51,54 +122,288 @@ pub async fn create_puzzle_tree(
     solver_color: Color,
     pre_cp:       i32,
     d:            &DepthSet,
+    opts:         TreeOptions,
+    history:      &[u64],
 ) -> Result<Option<PuzzleSeq>> {
     if !puzzle_is_interesting(engine, start, solver_color, pre_cp, d.solve).await? { return Ok(None); }
 
-    let mut seq        = Vec::<Move>::new();
-    let mut alt_lines  = Vec::<Vec<Move>>::new();
-    let mut board      = start.clone();
-    let mut last_cp    = pre_cp;
-    let mut last_mate  = false;
-    let mut solver_cnt = 0u8;
-
-    loop {
-        let sr = match solver_response(engine, &board, solver_color, pre_cp, d).await? {
-            None                      => break,
-            Some(r) if  r.ambiguous   => break,
-            Some(r)                   => r,
-        };
-
-        seq.push(sr.solution_move.clone());
-        solver_cnt += 1;
-        last_cp   = sr.post_cp;
-        last_mate = Engine::is_mate(&sr.score);
-
-        if config::MAX_ALTERNATIVE_LINES > 0 {
-            let keep: Vec<_> = sr.alternative_moves
-                .iter()
-                .take(config::MAX_ALTERNATIVE_LINES as usize)
-                .cloned()
-                .collect();
-            if !keep.is_empty() { alt_lines.push(keep); }
+    let ctx = LineCtx {
+        d,
+        opts,
+        solver_color,
+        pre_cp,
+        base_material: material_balance(start.board(), solver_color),
+    };
+    let mut nodes = 0usize;
+    let reps = RepetitionHistory::new(history.to_vec());
+    let mut line = build_line(engine, start.clone(), reps, &ctx, &mut nodes).await?;
+    if line.unique.len() < config::SOLVER_MIN_MOVES as usize { return Ok(None); }
+
+    // final confirmado como empate pela tablebase
+    if line.draw.is_none() {
+        let end = replay_line(start, &line.moves);
+        let wdl = engine.tablebase_wdl(&end);
+        if matches!(wdl, Some(Wdl::Draw | Wdl::BlessedLoss | Wdl::CursedWin)) { line.draw = Some(DrawKind::TablebaseDraw); }
+    }
+    debug!("árvore: {} lances na linha principal, {} nós", line.moves.len(), nodes);
+    Ok(Some(line))
+}
+
+/// Parâmetros fixos durante a construção da árvore.
+struct LineCtx<'a> {
+    d:             &'a DepthSet,
+    opts:          TreeOptions,
+    solver_color:  Color,
+    pre_cp:        i32,
+    base_material: i32,           // material do resolvedor na posição inicial do puzzle
+}
+
+/// Linha S‑O‑S… a partir de `start` (resolvedor a mover). Em cada nó do
+/// adversário, as defesas dentro de `DEFENSE_MARGIN` da melhor viram
+/// variantes, cada uma exigindo resposta única do resolvedor; se alguma não
+/// tiver, a linha termina no lance anterior do resolvedor.
+///
+/// Corte da linha: no mate; quando a vitória já é decisiva e não exige mais
+/// precisão (o segundo melhor lance também esmaga); ou quando o material já
+/// foi recuperado e a resposta do adversário não o devolve.
+fn build_line<'a>(
+    engine: &'a mut Engine,
+    start:  Chess,
+    mut reps: RepetitionHistory,
+    ctx:    &'a LineCtx<'a>,
+    nodes:  &'a mut usize,
+) -> LocalBoxFuture<'a, Result<PuzzleSeq>> {
+    async move {
+        let LineCtx { d, opts, solver_color, pre_cp, .. } = *ctx;
+        let sign = if solver_color == Color::White { 1 } else { -1 };
+        let mut seq        = Vec::<Move>::new();
+        let mut unique     = Vec::<bool>::new();
+        let mut variations = Vec::<Variation>::new();
+        let mut evals      = Vec::<Option<i32>>::new();
+        let mut refuted    = Vec::<Refutation>::new();
+        let mut draw       = None;
+        let mut ends_reply = false;               // empate forçado pela resposta: mantém o lance
+        let mut board      = start;
+        reps.push(&board, None);
+        let mut last_cp    = pre_cp;
+        let mut last_mate  = false;
+
+        while *nodes < opts.max_nodes {
+            if board.is_game_over() { break; }      // ex.: defesa de variante que afoga o resolvedor
+            let sr = match solver_response(engine, &board, solver_color, pre_cp, d).await? {
+                None                      => break,
+                Some(r) if  r.ambiguous   => break,
+                Some(r)                   => r,
+            };
+
+            // vitória decisiva que não exige mais precisão
+            let trimmable = unique.len() >= config::SOLVER_MIN_MOVES as usize && !Engine::is_mate(&sr.score);
+            if trimmable && sr.second_cp.is_some_and(|cp| sign * cp >= config::COMPLETELY_WINNING_THRESHOLD) {
+                trace!("corte: segundo melhor lance também decisivo");
+                break;
+            }
+
+            seq.push(sr.solution_move.clone());
+            evals.push(Some(sr.post_cp));
+            unique.push(sr.alternative_moves.is_empty());
+            *nodes   += 1;
+            last_cp   = sr.post_cp;
+            last_mate = Engine::is_mate(&sr.score);
+
+            // lances equivalentes do resolvedor: variantes no mesmo ponto
+            let ply = seq.len() - 1;
+            variations.extend(
+                sr.alternative_lines
+                    .iter()
+                    .take(config::MAX_ALTERNATIVE_LINES as usize)
+                    .map(|pv| Variation { ply, moves: solver_line(pv), variations: Vec::new() }),
+            );
+            refuted.extend(tempting_mistakes(&board, &sr.wrong_lines, opts.mistakes).into_iter().map(|(pv, cp)| {
+                let line = pv[..pv.len().min(config::REFUTATION_PLIES)].to_vec();
+                Refutation { ply, line, cp: *cp }
+            }));
+
+            board.play_unchecked(&sr.solution_move);
+            reps.push(&board, Some(board.is_check()));
+            if board.is_checkmate() { break; }
+            if board.is_stalemate() { draw = Some(DrawKind::Stalemate); break; }
+            if let Some(kind) = reps.threefold() { draw = Some(kind); break; }
+
+            let defenses = opponent_defenses(engine, &board, solver_color, d.solve, opts).await?;
+            let Some(((reply, reply_cp), others)) = defenses.split_first() else { break };
+
+            // vantagem convertida: material recuperado e mantido após a resposta
+            if trimmable && sign * sr.post_cp >= config::WINNING_ADVANTAGE {
+                let after  = replay_line(&board, std::slice::from_ref(reply));
+                let gained = material_balance(after.board(), solver_color) - ctx.base_material;
+                if gained >= config::CONVERSION_MIN_CP {
+                    trace!("corte: +{gained} cp de material convertidos");
+                    break;
+                }
+            }
+
+            // cada defesa alternativa precisa de resposta única do resolvedor
+            let ply = seq.len();
+            let mut branches = Vec::new();
+            let mut resolved = true;
+            for (def, _) in others {
+                // orçamento esgotado: defesa não verificada não conta como respondida
+                if *nodes >= opts.max_nodes { resolved = false; break; }
+                let mut b = board.clone();
+                b.play_unchecked(def);
+                *nodes += 1;
+                let sub = build_line(engine, b, reps.clone(), ctx, nodes).await?;
+                if sub.moves.is_empty() { resolved = false; break; }
+
+                let mut moves = vec![def.clone()];
+                moves.extend(sub.moves);
+                let nested = sub.variations.iter().map(|v| v.shifted(1)).collect();
+                branches.push(Variation { ply, moves, variations: nested });
+            }
+            if !resolved {
+                trace!("defesa sem resposta única após {} lances", seq.len());
+                break;
+            }
+
+            variations.extend(branches);
+            seq.push(reply.clone());
+            evals.push(*reply_cp);
+            *nodes += 1;
+            board.play_unchecked(reply);
+            reps.push(&board, None);
+            if board.is_checkmate() { break; }
+            // ex.: captura forçada que deixa o resolvedor afogado
+            if board.is_stalemate() { draw = Some(DrawKind::Stalemate); ends_reply = true; break; }
+            if let Some(kind) = reps.threefold() { draw = Some(kind); ends_reply = true; break; }
+        }
+
+        // a linha termina num lance do resolvedor, salvo empate forçado pela resposta
+        if seq.len() % 2 == 0 && !ends_reply && seq.pop().is_some() {
+            evals.pop();
+            variations.retain(|v| v.ply < seq.len());
         }
 
-        board.play_unchecked(&sr.solution_move);
+        Ok(PuzzleSeq {
+            moves:        seq,
+            unique,
+            variations,
+            final_cp:     last_cp,
+            is_mate:      last_mate,
+            comments:     Vec::new(),
+            evals,
+            glyphs:       Vec::new(),
+            refutations:  refuted,
+            draw,
+        })
+    }
+    .boxed_local()
+}
+
+/// Erros tentadores na ordem do motor ou, com `Forcing`, xeques e capturas
+/// primeiro; no máximo `REFUTATION_LINES`.
+fn tempting_mistakes<'l>(board: &Chess, wrong: &'l [(Vec<Move>, i32)], policy: MistakePolicy) -> Vec<&'l (Vec<Move>, i32)> {
+    let tempting = |pv: &[Move]| {
+        pv.first().is_some_and(|mv| mv.is_capture() || replay_line(board, std::slice::from_ref(mv)).is_check())
+    };
+    let mut picked: Vec<_> = wrong.iter().filter(|(pv, _)| !pv.is_empty()).collect();
+    if policy == MistakePolicy::Forcing {
+        picked.sort_by_key(|(pv, _)| !tempting(pv));       // estável: mantém a ordem do motor
+    }
+    picked.truncate(config::REFUTATION_LINES);
+    picked
+}
+
+/// PV de uma alternativa cortada em `ALTERNATIVE_LINE_PLIES`, terminando
+/// num lance do resolvedor (tamanho ímpar).
+fn solver_line(pv: &[Move]) -> Vec<Move> {
+    let mut len = pv.len().min(config::ALTERNATIVE_LINE_PLIES);
+    if len.is_multiple_of(2) { len = len.saturating_sub(1); }
+    pv[..len].to_vec()
+}
+
+/// Defesas do adversário dentro de `DEFENSE_MARGIN` (ou `MATE_ALT_THRESHOLD`
+/// em mates) da melhor; a escolhida pela política vem primeiro, seguida de
+/// até `branching` alternativas. A melhor do motor fica sempre entre elas,
+/// para que a solução seja verificada contra a defesa mais forte.
+async fn opponent_defenses(
+    engine:       &mut Engine,
+    board:        &Chess,
+    solver_color: Color,
+    depth:        u8,
+    opts:         TreeOptions,
+) -> Result<Vec<(Move, Option<i32>)>> {
+    let lines = match opts.defense {
+        DefensePolicy::Best => opts.branching + 1,
+        _                   => (opts.branching + 1).max(config::DEFENSE_POLICY_LINES),
+    };
+    let infos = engine.analyze(board, depth, lines as u32).await?;
+    let Some(base) = infos.first().and_then(|i| i.score.as_ref()) else { return Ok(Vec::new()) };
+    let thr = if Engine::is_mate(base) { config::MATE_ALT_THRESHOLD as i64 }
+              else                     { config::DEFENSE_MARGIN     as i64 };
+    let close: Vec<&AnalysisInfo> = infos
+        .iter()
+        .take_while(|i| i.score.as_ref().is_some_and(|s| Engine::key_diff(base, s) <= thr))
+        .filter(|i| !i.pv.is_empty())
+        .collect();
+
+    let pick = match opts.defense {
+        DefensePolicy::Best => 0,
+        policy              => {
+            let probes = probe_defenses(engine, board, &close, depth, policy).await?;
+            match policy {
+                DefensePolicy::Trickiest => trickiest(&probes, solver_color),
+                _                        => longest_resistance(&probes, solver_color),
+            }
+        }
+    };
+    let mut moves: Vec<(Move, Option<i32>)> =
+        close.iter().map(|i| (i.pv[0].clone(), i.score.as_ref().map(Engine::to_cp))).collect();
+    // [escolhida, melhor do motor, ...]: a melhor não some com `--branching 0`
+    if pick < moves.len() { moves[..=pick].rotate_right(1); }
+    moves.truncate(1 + opts.branching.max(usize::from(pick > 0)));
+    Ok(moves)
+}
 
-        let Some(bm) = engine.best_move(&board, d.solve).await? else { break };
-        let reply = bm.r#move.to_move(&board)?;
-        seq.push(reply.clone());
-        board.play_unchecked(&reply);
+/// Análise do resolvedor após cada defesa: melhor resposta primeiro
+/// (`TRICKY_MPV` linhas para `trickiest`, uma para as demais políticas).
+async fn probe_defenses(
+    engine: &mut Engine,
+    board:  &Chess,
+    close:  &[&AnalysisInfo],
+    depth:  u8,
+    policy: DefensePolicy,
+) -> Result<Vec<Vec<AnalysisInfo>>> {
+    let mpv = if policy == DefensePolicy::Trickiest { config::TRICKY_MPV } else { 1 };
+    let mut probes = Vec::with_capacity(close.len());
+    for info in close {
+        let mut b = board.clone();
+        b.play_unchecked(&info.pv[0]);
+        probes.push(engine.analyze(&b, depth, mpv).await?);
     }
+    Ok(probes)
+}
 
-    if solver_cnt < config::SOLVER_MIN_MOVES { return Ok(None); }
-    if seq.len() % 2 == 0 { seq.pop(); }
+/// Índice da defesa que minimiza a avaliação do resolvedor após a melhor
+/// resposta dele (mate mais distante conta como avaliação menor).
+fn longest_resistance(probes: &[Vec<AnalysisInfo>], solver_color: Color) -> usize {
+    let sign = if solver_color == Color::White { 1 } else { -1 };
+    let eval = |lines: &Vec<AnalysisInfo>| {
+        lines.first().and_then(|i| i.score.as_ref()).map_or(i64::MAX, |s| sign * Engine::key(s))
+    };
+    // min_by_key devolve o primeiro em empate: mantém a ordem do motor
+    probes.iter().enumerate().min_by_key(|(_, lines)| eval(lines)).map_or(0, |(k, _)| k)
+}
 
-    Ok(Some(PuzzleSeq {
-        moves:        seq,
-        alternatives: alt_lines,
-        final_cp:     last_cp,
-        is_mate:      last_mate,
-    }))
+/// Índice da defesa após a qual o resolvedor tem menos respostas vencedoras.
+fn trickiest(probes: &[Vec<AnalysisInfo>], solver_color: Color) -> usize {
+    let sign = if solver_color == Color::White { 1 } else { -1 };
+    let winning = |lines: &Vec<AnalysisInfo>| {
+        lines
+            .iter()
+            .filter(|i| i.score.as_ref().is_some_and(|s| sign * Engine::to_cp(s) >= config::WINNING_ADVANTAGE))
+            .count()
+    };
+    probes.iter().enumerate().min_by_key(|(_, lines)| winning(lines)).map_or(0, |(k, _)| k)
 }
 
 // ---------------------------------------------------------------------------
@@ -107,42 +412,96 @@ pub async fn create_puzzle_tree(
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GamePhase { Opening, Middlegame, Endgame }
 
-pub fn classify_phase(board: &Chess, plies: usize) -> GamePhase {
-    const W: [i32;5] = [0,1,1,2,4];
-    let mut phase = 2 * (W[0]*8 + W[1]*2 + W[2]*2 + W[3]*2 + W[4]);
-    let dec = |r: Role, w: i32, v: &mut i32| *v -= w * (board.our(r).0.count_ones() + board.their(r).0.count_ones()) as i32;
-    dec(Role::Pawn  , W[0], &mut phase);
-    dec(Role::Knight, W[1], &mut phase);
-    dec(Role::Bishop, W[2], &mut phase);
-    dec(Role::Rook  , W[3], &mut phase);
-    dec(Role::Queen , W[4], &mut phase);
-
-    let material = phase as f32 / 196.0;
-    let ply_norm = (plies as f32 / 80.0).min(1.0);
+/// Fase pelo material dos dois lados ("tapered": N=B=1, R=2, Q=4; 24 com
+/// todas as peças, 0 só com reis e peões), pela troca de damas e pelo
+/// desenvolvimento. `plies` é o índice do lance na partida em plies
+/// (`MoveRecord::move_idx`), não o número do lance.
+///
+/// * Final: fase ≤ `ENDGAME_PHASE`, ou sem damas e fase ≤ `QUEENLESS_ENDGAME_PHASE`.
+/// * Abertura: fase ≥ `OPENING_PHASE`, até `OPENING_MAX_PLIES` e os dois
+///   lados com ao menos `OPENING_BACKRANK` peças na primeira fila.
+/// * Meio‑jogo: o resto.
+pub fn classify_phase(board: &Chess, plies: u32) -> GamePhase {
+    let b     = board.board();
+    let count = |role: Role| b.by_role(role).count() as u32;
+    let phase = (count(Role::Knight) + count(Role::Bishop) + 2 * count(Role::Rook) + 4 * count(Role::Queen))
+        .min(config::PHASE_FULL);
 
-    let rights = [
-        (Color::White, CastlingSide::KingSide),
-        (Color::White, CastlingSide::QueenSide),
-        (Color::Black, CastlingSide::KingSide),
-        (Color::Black, CastlingSide::QueenSide),
-    ].iter().filter(|&&(c,s)| board.castles().has(c,s)).count() as f32 / 4.0;
+    let queens_off = count(Role::Queen) == 0;
+    if phase <= config::ENDGAME_PHASE || (queens_off && phase <= config::QUEENLESS_ENDGAME_PHASE) {
+        return GamePhase::Endgame;
+    }
 
-    let v = (material*2.0 + ply_norm + rights) / 4.0;
-    if v >= 0.80 { GamePhase::Opening }
-    else if v <= 0.20 { GamePhase::Endgame }
-    else { GamePhase::Middlegame }
+    // peças (rei incluído) ainda na primeira fila: pouco desenvolvimento
+    let home = |c: Color| (b.by_color(c) & !b.pawns() & Bitboard::from_rank(c.backrank())).count();
+    let undeveloped = Color::ALL.iter().all(|&c| home(c) >= config::OPENING_BACKRANK);
+    if phase >= config::OPENING_PHASE && plies <= config::OPENING_MAX_PLIES && undeveloped {
+        GamePhase::Opening
+    } else {
+        GamePhase::Middlegame
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Exporta PGN
 // ---------------------------------------------------------------------------
+/// Puzzle pronto para exportar + classificação usada em filtros e estatísticas.
+#[derive(Debug, Clone)]
+pub struct ProcessedPuzzle {
+    pub pgn:    String,
+    pub fen:    String,
+    pub phase:  GamePhase,
+    pub endgame: Option<EndgameCategory>, // família do final, quando a fase é de final
+    pub opening: Option<String>,          // família da abertura (header Opening)
+    pub tactic: TacticalObjective,
+    pub kind:    CandidateKind,
+    pub themes:  Vec<&'static str>,
+    pub trivial: Option<TrivialKind>,
+    pub refutations: Vec<RefutationRecord>,
+}
+
+/// Refutação exportada em JSON: índice no PGN do puzzle (0 = blunder),
+/// lances em SAN a partir do erro e avaliação após ele.
+#[derive(Debug, Clone, Serialize)]
+pub struct RefutationRecord {
+    pub ply:  usize,
+    pub line: Vec<String>,
+    pub cp:   i32,
+}
+
+
+impl ProcessedPuzzle {
+    pub fn has_theme(&self, theme: &str) -> bool { self.themes.contains(&theme) }
+
+    /// Verdadeiro se o puzzle contém todos os temas pedidos.
+    pub fn has_all_themes(&self, wanted: &[String]) -> bool {
+        wanted.iter().all(|t| self.has_theme(t))
+    }
+
+    /// Registra fase, objetivo, tipo e temas nas estatísticas (só após aceito).
+    /// Finais entram por família ("Endgame/Rook") para montar currículos.
+    pub fn record(&self, stats: &mut PuzzleStatistics) {
+        match self.endgame {
+            Some(category) => stats.update_phase(&format!("Endgame/{:?}", category), 1),
+            None           => stats.update_phase(&format!("{:?}", self.phase), 1),
+        }
+        stats.update_objective(&format!("{:?}", self.tactic), 1);
+        stats.update_kind(&format!("{:?}", self.kind), 1);
+        for theme in &self.themes { stats.update_theme(theme, 1); }
+        if let Some(family) = &self.opening { stats.update_opening(family, 1); }
+    }
+}
+
 /// Monta headers finais e delega ao `build_pgn_san`.
 pub fn process_puzzle(
     cand:    &PuzzleCandidate,
     seq:     &PuzzleSeq,
     headers: &[(String, String)],        // << novo parâmetro
-) -> Result<String> {
-    let phase  = classify_phase(&cand.board_post_blunder, cand.move_number as usize);
+    profile: Option<&SearchProfile>,     // busca iterativa (dificuldade), se feita
+    human:   Option<&HumanCheck>,        // motor enfraquecido por nível de Elo, se pedido
+    export:  ExportOptions,
+) -> Result<ProcessedPuzzle> {
+    let phase  = classify_phase(&cand.board_post_blunder, cand.move_number);
     let tactic = classify_tactic(cand.post_cp, seq.final_cp, seq.is_mate);
 
     let mut hdr: IndexMap<String, String> =
@@ -150,6 +509,61 @@ pub fn process_puzzle(
 
     hdr.insert("Phase".into(),    format!("{:?}", phase));
     hdr.insert("Tactical".into(), format!("{:?}", tactic));
+    if let Some(clk) = cand.blunder_clock {
+        let pressure = clk < config::TIME_PRESSURE_SECS;
+        hdr.insert("TimePressure".into(), if pressure { "Yes" } else { "No" }.into());
+    }
+    hdr.insert("Kind".into(), format!("{:?}", cand.kind));
+
+    // finais: assinatura material e família
+    let endgame = (phase == GamePhase::Endgame || cand.kind == CandidateKind::Study)
+        .then(|| classify_endgame(cand.board_post_blunder.board()));
+    if let Some(category) = endgame {
+        hdr.insert("Endgame".into(),     material_signature(cand.board_post_blunder.board()));
+        hdr.insert("EndgameType".into(), format!("{:?}", category));
+    }
+
+    let mut themes: Vec<&'static str> = detect_motifs(&cand.board_post_blunder, &seq.moves, cand.solver_color)
+        .into_iter()
+        .chain(detect_sequence_motifs(&cand.board_post_blunder, Some(&cand.blunder_move), &seq.moves))
+        .map(Motif::tag)
+        .collect();
+    themes.extend(detect_move_tags(&cand.board_post_blunder, &seq.moves, &seq.unique).into_iter().map(MoveTag::tag));
+    let final_board = replay_line(&cand.board_post_blunder, &seq.moves);
+    if final_board.is_checkmate() {
+        themes.extend(classify_mate(&final_board).map(|p| p.tag()));
+        themes.push(mate_length_tag(seq.moves.len().div_ceil(2)));
+    }
+
+    themes.extend(seq.draw.map(DrawKind::tag));
+    themes.extend(endgame.map(EndgameCategory::tag));
+
+    let mate  = seq.is_mate || final_board.is_checkmate();
+    let track = material_track(&cand.board_post_blunder, &seq.moves, cand.solver_color);
+    let sacrifice = detect_sacrifice(&track, mate);
+    if let Some(sac) = sacrifice {
+        themes.push("sacrifice");
+        hdr.insert("SacrificeInvested".into(), sac.invested.to_string());
+        hdr.insert("SacrificeNetGain".into(),  format!("{:+}", sac.net_gain));
+    }
+
+    let quiet      = themes.contains(&MoveTag::QuietMove.tag());
+    let difficulty = profile.map(|p| estimate(p, seq.moves.len().div_ceil(2), sacrifice.is_some(), quiet));
+    if let Some(d) = difficulty {
+        hdr.insert("PuzzleRating".into(), d.rating.to_string());
+        hdr.insert("Difficulty".into(),   d.score.to_string());
+    }
+    if let Some(h) = human {
+        hdr.insert("HumanCheck".into(), h.summary());
+        hdr.insert("HumanBand".into(),  h.band());
+    }
+
+    let net_gain = track.last().zip(track.first()).map_or(0, |(l, f)| l - f);
+    let trivial  = seq.moves.first().and_then(|first| {
+        trivial_first_move(&cand.board_post_blunder, &cand.blunder_move, first, net_gain, mate)
+    });
+    themes.extend(trivial.map(TrivialKind::tag));
+    if !themes.is_empty() { hdr.insert("Themes".into(), themes.join(" ")); }
     hdr.insert("SetUp".into(),    "1".into());
     hdr.insert(
         "FEN".into(),
@@ -160,5 +574,166 @@ pub fn process_puzzle(
     moves.push(cand.blunder_move.clone());
     moves.extend(seq.moves.iter().cloned());
 
-    build_pgn_san(&hdr, &PuzzleSeq { moves, ..seq.clone() })
+    // comentários do solver deslocados de 1 (o blunder abre a sequência)
+    let mut comments: Vec<(usize, String)> =
+        seq.comments.iter().map(|(i, c)| (i + 1, c.clone())).collect();
+    if let (CandidateKind::MissedWin, Some(reply)) = (cand.kind, &cand.played_reply) {
+        comments.insert(0, (0, format!("Na partida: {}, deixando escapar o ganho", played_san(cand, reply))));
+    }
+    if let Some((alt, alt_cp)) = &cand.best_alternative {
+        comments.insert(0, (0, alternative_comment(cand, alt, *alt_cp)));
+    }
+
+    let mut variations: Vec<Variation> = seq.variations.iter().map(|v| v.shifted(1)).collect();
+    let (mut evals, mut glyphs) = (Vec::new(), Vec::new());
+    if export.annotate {
+        evals.push(Some(cand.post_cp));
+        evals.extend(seq.evals.iter().copied());
+        if !matches!(cand.kind, CandidateKind::OnlyMove | CandidateKind::Study) { glyphs.push((0, "??")); }
+        glyphs.extend(seq.unique.iter().enumerate().filter(|(_, u)| **u).map(|(i, _)| (2 * i + 1, "!")));
+        for r in &seq.refutations {
+            let at = replay_line(&cand.board_post_blunder, &seq.moves[..r.ply]);
+            comments.push((r.ply + 1, refutation_comment(&at, r, cand.solver_color)));
+        }
+    }
+    if export.refutations == RefutationExport::Variations {
+        variations.extend(seq.refutations.iter().map(|r| Variation { ply: r.ply + 1, moves: r.line.clone(), variations: Vec::new() }));
+    }
+    let pgn = build_pgn_san(&hdr, &PuzzleSeq { moves, comments, variations, evals, glyphs, ..seq.clone() })?;
+
+    let refutations = seq.refutations.iter().map(|r| {
+        let at = replay_line(&cand.board_post_blunder, &seq.moves[..r.ply]);
+        RefutationRecord {
+            ply:  r.ply + 1,
+            line: san_moves(&at, &r.line),
+            cp:   r.cp,
+        }
+    }).collect();
+    Ok(ProcessedPuzzle {
+        pgn,
+        fen: hdr.get("FEN").cloned().unwrap_or_default(),
+        phase,
+        endgame,
+        opening: hdr.get("Opening").filter(|name| !name.is_empty() && *name != "?").map(|name| eco::family(name).to_string()),
+        tactic,
+        kind: cand.kind,
+        themes,
+        trivial,
+        refutations,
+    })
+}
+
+/// "24.Qxd5? Rxd5 e as pretas seguram": por que o erro plausível não funciona.
+fn refutation_comment(at: &Chess, r: &Refutation, solver: Color) -> String {
+    let sign     = if solver == Color::White { 1 } else { -1 };
+    let opponent = if solver == Color::White { "as pretas" } else { "as brancas" };
+    let verdict  = match sign * r.cp {
+        cp if cp <= -config::WINNING_ADVANTAGE => format!("e {opponent} vencem"),
+        cp if cp <   config::WINNING_ADVANTAGE => format!("e {opponent} seguram"),
+        _                                      => "com vantagem bem menor".into(),
+    };
+    format!("{} {verdict}", san_line(at, &r.line[..r.line.len().min(2)], &[(0, "?")]))
+}
+
+/// "Em vez disso, 23.Rd1 segurava": lance que evitava o blunder.
+fn alternative_comment(cand: &PuzzleCandidate, alt: &Move, alt_cp: i32) -> String {
+    let b    = &cand.board_pre_blunder;
+    let sep  = if b.turn() == Color::White { "." } else { "..." };
+    let sign = if b.turn() == Color::White { 1 } else { -1 };
+    let verb = if sign * alt_cp >= config::WINNING_ADVANTAGE { "mantinha a vantagem" } else { "segurava" };
+    format!("Em vez disso, {}{}{} {}", b.fullmoves(), sep, San::from_move(b, alt), verb)
+}
+
+/// SAN numerado da resposta jogada na partida (ex.: "24.Qd2").
+fn played_san(cand: &PuzzleCandidate, reply: &Move) -> String {
+    let b   = &cand.board_post_blunder;
+    let sep = if b.turn() == Color::White { "." } else { "..." };
+    format!("{}{}{}", b.fullmoves(), sep, San::from_move(b, reply))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruci::engine::Score as RuciScore;
+    use shakmaty::CastlingMode;
+
+    use crate::engine::AnalysisOrigin;
+
+    fn position(fen: &str) -> Chess {
+        fen.parse::<Fen>().unwrap().into_position(CastlingMode::Standard).unwrap()
+    }
+
+    fn probe(scores: &[RuciScore]) -> Vec<AnalysisInfo> {
+        scores.iter().map(|s| AnalysisInfo {
+            score:    Some(s.standardized(Color::White)),
+            depth:    None,
+            seldepth: None,
+            nodes:    None,
+            pv:       Vec::new(),
+            origin:   AnalysisOrigin::Engine,
+        }).collect()
+    }
+
+    #[test]
+    fn longest_resistance_minimizes_solver_eval() {
+        use RuciScore::*;
+        // a melhor defesa do motor (índice 0) deixa +400; a segunda, só +180
+        let probes = [probe(&[Centipawns(400)]), probe(&[Centipawns(180)]), probe(&[Centipawns(250)])];
+        assert_eq!(longest_resistance(&probes, Color::White), 1);
+        // mate mais distante resiste mais; pretas resolvendo invertem o sinal
+        let mates = [probe(&[MateIn(2)]), probe(&[MateIn(3)]), probe(&[MateIn(5)])];
+        assert_eq!(longest_resistance(&mates, Color::White), 2);
+        let black = [probe(&[Centipawns(-400)]), probe(&[Centipawns(-150)])];
+        assert_eq!(longest_resistance(&black, Color::Black), 1);
+        // empate mantém a ordem do motor
+        let tied = [probe(&[Centipawns(300)]), probe(&[Centipawns(300)])];
+        assert_eq!(longest_resistance(&tied, Color::White), 0);
+    }
+
+    #[test]
+    fn trickiest_counts_winning_replies() {
+        use RuciScore::*;
+        let probes = [
+            probe(&[Centipawns(500), Centipawns(450), Centipawns(300)]),
+            probe(&[Centipawns(600), Centipawns(20)]),
+        ];
+        assert_eq!(trickiest(&probes, Color::White), 1);
+    }
+
+    #[test]
+    fn mistake_policies() {
+        let pos   = position("4k3/8/8/3p4/8/8/8/3RK3 w - - 0 1");
+        let uci   = |s: &str| s.parse::<UciMove>().unwrap().to_move(&pos).unwrap();
+        let wrong = [(vec![uci("e1e2")], -50), (vec![uci("d1d5")], -120)];
+        let first = |policy| tempting_mistakes(&pos, &wrong, policy)[0].0[0].clone();
+        assert_eq!(first(MistakePolicy::Engine),  uci("e1e2"));
+        assert_eq!(first(MistakePolicy::Forcing), uci("d1d5"));
+    }
+
+    #[test]
+    fn known_phases() {
+        use GamePhase::*;
+        let cases = [
+            // posição inicial e abertura italiana (6 plies)
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",                  0, Opening),
+            ("r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",       6, Opening),
+            // material completo, mas tarde demais para ser abertura
+            ("r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",      40, Middlegame),
+            // peças desenvolvidas: primeira fila das brancas quase vazia
+            ("r2q1rk1/pp2bppp/2n1pn2/3p4/3P4/2NBPN2/PPQ2PPP/4RRK1 w - - 0 12",           22, Middlegame),
+            // damas trocadas cedo, resto completo: meio‑jogo sem damas
+            ("r1b1kb1r/ppp2ppp/2n2n2/4p3/4P3/2N2N2/PPP2PPP/R1B1KB1R w KQkq - 0 6",       10, Middlegame),
+            // damas trocadas com TT+B de cada lado: regra sem damas → final
+            ("2r2rk1/pp3ppp/2b5/8/8/2B5/PP3PPP/2R2RK1 w - - 0 25",                       48, Endgame),
+            // damas na mesa com D+B×D: ainda meio‑jogo; D×D: final
+            ("6k1/5ppp/8/3q4/8/2B5/5PPP/3Q2K1 w - - 0 40",                               78, Middlegame),
+            ("6k1/5ppp/8/3q4/8/8/5PPP/3Q2K1 w - - 0 40",                                 78, Endgame),
+            // finais de torre e de peões
+            ("8/5k2/8/3r4/8/3RP3/5K2/8 w - - 0 50",                                      98, Endgame),
+            ("8/5kp1/8/8/8/8/4KP2/8 w - - 0 50",                                         98, Endgame),
+        ];
+        for (fen, plies, expected) in cases {
+            assert_eq!(classify_phase(&position(fen), plies), expected, "{fen}");
+        }
+    }
 }
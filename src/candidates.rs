@@ -1,12 +1,17 @@
 // src/candidates.rs
 // ---------------------------------------------------------------------------
 // Varredura de blunders: 1 chamada ao engine por lance. Fila de candidatos.
+// No modo defensivo, +1 chamada MultiPV por posição para achar "lance único".
+// Com livro de aberturas, os lances de teoria não são analisados.
 // ---------------------------------------------------------------------------
 
 use anyhow::Result;
+use clap::ValueEnum;
 use shakmaty::{Chess, Color, Move, Position};
 use crate::{
+    book::Book,
     config,
+    draws::position_key,
     engine::Engine,
     utils::{DepthSet, MoveRecord},
     visual::CustomProgressBar,
@@ -15,6 +20,33 @@ use crate::{
 pub struct CandidateContext<'a> {
     engine:       &'a mut Engine,
     progress_bar: Option<&'a CustomProgressBar>,
+    mode:         ExtractionMode,
+    book:         Option<&'a Book>,
+}
+
+/// Quais tipos de candidato a varredura procura.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExtractionMode {
+    /// Grandes oscilações de avaliação após um blunder
+    Blunders,
+    /// Posições com um único lance que segura, haja blunder antes ou não
+    Defensive,
+    /// Ambos
+    All,
+    /// Estudos de final: lance único pela tablebase, sem motor (requer SYZYGY_PATHS)
+    Endgame,
+    /// Armadilhas de abertura: o primeiro lance fora do livro já perde (requer --book)
+    Traps,
+}
+
+/// Origem do candidato e, para blunders, como a partida real seguiu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandidateKind {
+    Blunder,    // resposta desconhecida (fim da partida)
+    Punished,   // o adversário manteve a vantagem com a resposta jogada
+    MissedWin,  // a resposta jogada devolveu a vantagem
+    OnlyMove,   // defesa: só um lance segura, todos os outros perdem
+    Study,      // final: só um lance preserva o resultado da tablebase
 }
 
 #[derive(Debug, Clone)]
@@ -25,7 +57,12 @@ pub struct PuzzleCandidate {
     pub solver_color      : Color,
     pub pre_cp            : i32,
     pub post_cp           : i32,
-    pub move_number       : u32,
+    pub move_number       : u32,            // ply do blunder na partida (MoveRecord::move_idx)
+    pub played_reply      : Option<Move>,   // resposta efetivamente jogada na partida
+    pub blunder_clock     : Option<u32>,    // relógio (s) do blunderer após o erro
+    pub kind              : CandidateKind,
+    pub best_alternative  : Option<(Move, i32)>, // lance que evitava o blunder e seu cp (pré‑posição)
+    pub history           : Vec<u64>,       // chaves das posições da partida antes do início do puzzle
 }
 
 impl<'a> CandidateContext<'a> {
@@ -33,8 +70,10 @@ impl<'a> CandidateContext<'a> {
     pub fn new(
         engine:       &'a mut Engine,
         progress_bar: Option<&'a CustomProgressBar>,
+        mode:         ExtractionMode,
+        book:         Option<&'a Book>,
     ) -> Self {
-        Self { engine, progress_bar }
+        Self { engine, progress_bar, mode, book }
     }
 
     pub async fn collect_candidates<I>(
@@ -49,15 +88,89 @@ impl<'a> CandidateContext<'a> {
         let init = self.engine.analyze(&board, depths.scan, 1).await?[0]
             .score.as_ref().unwrap().clone();
         let mut prev_cp = Engine::to_cp(&init);
-        let mut pool = Vec::new();
+        let mut pool: Vec<(PuzzleCandidate, Vec<(String, String)>)> = Vec::new();
+        let mut last: Option<(Chess, Move, i32, u32)> = None;   // lance anterior (pré, lance, cp, nº)
+        let mut keys: Vec<u64> = Vec::new();                     // posições da partida (repetição)
+        let mut in_book = self.book.is_some();                   // ainda na teoria?
+        let mut book_exit: Option<u32> = None;                   // ply do primeiro lance fora do livro
 
         for rec in games {
+            // Novo jogo: reinicia tabuleiro e avaliação em vez de herdar a
+            // posição final da partida anterior.
+            if rec.move_idx == 1 {
+                last = None;
+                keys.clear();
+                in_book   = self.book.is_some();
+                book_exit = None;
+                if rec.board != board {
+                    board   = rec.board.clone();
+                    prev_cp = Engine::to_cp(
+                        self.engine.analyze(&board, depths.scan, 1).await?[0].score.as_ref().unwrap(),
+                    );
+                }
+            }
+
+            // Lances de livro: sem análise; ao sair, avalia a posição uma vez
+            if in_book {
+                if self.book.is_some_and(|b| b.contains(&board, &rec.mv)) {
+                    keys.push(position_key(&board));
+                    board.play_unchecked(&rec.mv);
+                    continue;
+                }
+                in_book   = false;
+                book_exit = Some(rec.move_idx);
+                if rec.move_idx > 1 {
+                    prev_cp = Engine::to_cp(
+                        self.engine.analyze(&board, depths.scan, 1).await?[0].score.as_ref().unwrap(),
+                    );
+                }
+            }
+            // Armadilhas: só o primeiro lance fora do livro e a resposta (tipo do candidato)
+            if self.mode == ExtractionMode::Traps && book_exit.is_none_or(|exit| rec.move_idx > exit + 1) {
+                board.play_unchecked(&rec.mv);
+                continue;
+            }
+
+            // Modo defensivo: a posição atual (após o lance anterior) tem lance único?
+            let covered = pool.last().is_some_and(|(c, _)| c.board_post_blunder == board);
+            if let (true, false, Some((pre, prev_mv, pre_cp, prev_no))) =
+                (matches!(self.mode, ExtractionMode::Defensive | ExtractionMode::All), covered, &last)
+            {
+                if let Some(mut cand) = self.find_only_move(pre, prev_mv, &board, *pre_cp, depths, *prev_no).await? {
+                    cand.played_reply = Some(rec.mv.clone());
+                    cand.history      = keys.clone();
+                    pool.push((cand, rec.headers.clone()));
+                }
+            }
+
+            keys.push(position_key(&board));
+
+            // O lance seguinte ao blunder é a resposta real do jogador
+            let is_reply = matches!(
+                pool.last(),
+                Some((last, _)) if last.played_reply.is_none()
+                    && rec.move_idx == last.move_number + 1
+                    && rec.board == last.board_post_blunder
+            );
+
             let (next_cp, maybe_cand) = self
                 .find_candidate(&board, &rec.mv, prev_cp, depths, rec.move_idx)
                 .await?;
-            if let Some(cand) = maybe_cand {
+            if let (true, Some((cand, _))) = (is_reply, pool.last_mut()) {
+                cand.played_reply = Some(rec.mv.clone());
+                cand.kind         = reply_kind(cand, next_cp);
+            }
+            let wanted = |cand: &PuzzleCandidate| match self.mode {
+                ExtractionMode::Blunders | ExtractionMode::All => true,
+                ExtractionMode::Traps => book_exit == Some(rec.move_idx) && loses_immediately(cand),
+                _ => false,
+            };
+            if let Some(mut cand) = maybe_cand.filter(wanted) {
+                cand.blunder_clock = rec.clock;
+                cand.history       = keys.clone();
                 pool.push((cand, rec.headers));
             }
+            last = Some((board.clone(), rec.mv.clone(), prev_cp, rec.move_idx));
             board.play_unchecked(&rec.mv);
             prev_cp = next_cp;
         }
@@ -104,7 +217,77 @@ impl<'a> CandidateContext<'a> {
                 pre_cp            : prev_cp,
                 post_cp,
                 move_number       : move_no,
+                played_reply      : None,
+                blunder_clock     : None,
+                kind              : CandidateKind::Blunder,
+                best_alternative  : None,
+                history           : Vec::new(),
             }),
         ))
     }
 }
+
+impl CandidateContext<'_> {
+    /// Lance único defensivo: o melhor lance segura (não perde por margem
+    /// decisiva, nem já vence) e o segundo perde por pelo menos
+    /// `PUZZLE_UNICITY_THRESHOLD`. O lance anterior da partida abre o puzzle.
+    async fn find_only_move(
+        &mut self,
+        board_pre: &Chess,
+        prev_mv:   &Move,
+        board:     &Chess,
+        pre_cp:    i32,
+        depths:    &DepthSet,
+        move_no:   u32,
+    ) -> Result<Option<PuzzleCandidate>> {
+        if board.legal_moves().len() <= 1 { return Ok(None); }
+
+        let infos = self.engine.analyze(board, depths.scan, 2).await?;
+        let [best, second] = match infos.as_slice() {
+            [b, s, ..] => [b, s].map(|i| i.score.as_ref().map(Engine::to_cp)),
+            _          => return Ok(None),
+        };
+        let (Some(best), Some(second)) = (best, second) else { return Ok(None) };
+
+        // avaliações do ponto de vista de quem defende
+        let sign = if board.turn() == Color::White { 1 } else { -1 };
+        let (best_pov, second_pov) = (sign * best, sign * second);
+        let holds = best_pov > -config::WINNING_ADVANTAGE && best_pov < config::WINNING_ADVANTAGE;
+        let loses = second_pov <= -config::WINNING_ADVANTAGE
+            && best_pov - second_pov >= config::PUZZLE_UNICITY_THRESHOLD;
+        if !(holds && loses) { return Ok(None); }
+
+        Ok(Some(PuzzleCandidate {
+            board_pre_blunder : board_pre.clone(),
+            board_post_blunder: board.clone(),
+            blunder_move      : prev_mv.clone(),
+            solver_color      : board.turn(),
+            pre_cp,
+            post_cp           : best,
+            move_number       : move_no,
+            played_reply      : None,
+            blunder_clock     : None,
+            kind              : CandidateKind::OnlyMove,
+            best_alternative  : None,
+            history           : Vec::new(),
+        }))
+    }
+}
+
+/// Armadilha: quem errou não estava perdido antes e fica perdido logo após o lance.
+fn loses_immediately(cand: &PuzzleCandidate) -> bool {
+    let sign = if cand.solver_color == Color::White { 1 } else { -1 };
+    sign * cand.pre_cp < config::WINNING_ADVANTAGE && sign * cand.post_cp >= config::WINNING_ADVANTAGE
+}
+
+/// Classifica a resposta jogada: devolver ao menos `BLUNDER_THRESHOLD` e sair
+/// da vantagem decisiva significa que o blunder não foi punido.
+fn reply_kind(cand: &PuzzleCandidate, reply_cp: i32) -> CandidateKind {
+    let sign = if cand.solver_color == Color::White { 1 } else { -1 };
+    let lost = sign * (cand.post_cp - reply_cp);
+    if lost >= config::BLUNDER_THRESHOLD && sign * reply_cp < config::WINNING_ADVANTAGE {
+        CandidateKind::MissedWin
+    } else {
+        CandidateKind::Punished
+    }
+}
@@ -3,7 +3,12 @@
 // Configurações padrão para argumentos da linha de comando
 pub const DEFAULT_DEPTH: u8 = 16;                  // Profundidade padrão para análise
 pub const MAX_ALTERNATIVE_LINES: u8 = 2;           // Número máximo de linhas alternativas completas
+pub const REFUTATION_PLIES: usize = 4;             // Lance errado + linha que o refuta
+pub const REFUTATION_LINES: usize = 2;             // Erros tentadores guardados por nó do resolvedor
+pub const ALTERNATIVE_LINE_PLIES: usize = 5;       // Plies de cada linha alternativa (termina em lance do resolvedor)
 pub const SOLVER_MIN_MOVES: u8 = 2;                // Mínimo de lances do resolvedor
+pub const DEFAULT_BRANCHING: usize = 2;            // Defesas alternativas do adversário exploradas por nó
+pub const DEFAULT_MAX_NODES: usize = 40;           // Lances na árvore de solução inteira
 
 // Para uma varredura ainda mais rápida com soluções muito profundas
 pub const SCAN_DEPTH_MULTIPLIER: f32 = 1.0;        // Profundidade base para varredura
@@ -14,13 +19,51 @@ pub const PUZZLE_UNICITY_THRESHOLD: i32 = 200;     // Margem mínima para próxi
 pub const BLUNDER_THRESHOLD: i32 = 150;            // Queda mínima na avaliação para detectar um blunder (1.5 peão)
 pub const ALT_THRESHOLD: i32 = 25;                 // Diferença máxima (em cp) para considerar lances equivalentes (0.25 peão)
 pub const MATE_ALT_THRESHOLD: i32 = 2;             // Diferença máxima de plies para mates
+pub const DEFENSE_MARGIN: i32 = 50;                // Defesas do adversário até 0.5 peão da melhor viram variantes
+pub const DEFENSE_POLICY_LINES: usize = 3;         // Defesas comparadas pelas políticas longest-resistance/trickiest
+pub const TRICKY_MPV: u32 = 4;                     // Linhas do resolvedor contadas após cada defesa (trickiest)
 pub const COMPLETELY_WINNING_THRESHOLD: i32 = 500; // Limiar (em cp) para posição completamente ganha mesmo após erro (5 peões)
 pub const HANGING_THRESHOLD: i32 = 400;            // Limite mínimo de diferença para identificar hanging piece
+pub const CONVERSION_MIN_CP: i32 = 300;            // Material (cp) ganho e mantido que encerra a solução
+pub const SACRIFICE_MIN_CP: i32 = 200;             // Material mínimo (cp) entregue pelo resolvedor para contar como sacrifício
+pub const AVOIDABLE_MULTIPV: u32 = 3;              // Linhas analisadas antes do blunder para achar a alternativa que segurava
+
+// Fase do jogo (material "tapered": N=B=1, R=2, Q=4)
+pub const PHASE_FULL: u32 = 24;                    // Fase com todas as peças em jogo
+pub const ENDGAME_PHASE: u32 = 8;                  // Até aqui é final (ex.: D×D, T+B×T+B)
+pub const QUEENLESS_ENDGAME_PHASE: u32 = 10;       // Sem damas, final até aqui (ex.: TT+B×TT+B)
+pub const OPENING_PHASE: u32 = 20;                 // Abertura exige material quase completo
+pub const OPENING_MAX_PLIES: u32 = 30;             // ... e no máximo 15 lances
+pub const OPENING_BACKRANK: usize = 5;             // ... e cada lado com ≥ 5 peças na primeira fila (rei incluso)
+
+// Classificação ECO (tabela embutida)
+pub const ECO_MAX_PLIES: u32 = 40;                 // Plies da partida consultados na tabela
 
 // Constantes de valor em peões para avaliações
 pub const WINNING_ADVANTAGE: i32 = 150;            // Vantagem considerada decisiva (1.5 peão)
 pub const DRAWING_RANGE: i32 = 100;                // Intervalo para considerar posição como aproximadamente igualada (-1 a +1)
 
+// Estimativa de dificuldade (PuzzleRating)
+pub const RATING_BASE: i32 = 1000;                 // Rating de um mate em 2 óbvio
+pub const RATING_MIN: i32 = 600;                   // Limites do rating estimado
+pub const RATING_MAX: i32 = 2800;
+pub const RATING_PER_DEPTH: i32 = 40;              // Por ply de profundidade até o motor fixar a solução
+pub const RATING_PER_SOLVER_MOVE: i32 = 150;       // Por lance do resolvedor além do primeiro
+pub const RATING_PER_DECOY: i32 = 100;             // Por lance plausível errado preferido em profundidade rasa
+pub const RATING_SACRIFICE: i32 = 250;             // Bônus quando a linha contém sacrifício
+pub const RATING_QUIET: i32 = 200;                 // Bônus quando há lance silencioso
+
+// Checagem de "humanidade" com motor enfraquecido (--human-check)
+pub const HUMAN_ELO_STEPS: [u32; 5] = [1350, 1500, 1800, 2100, 2400]; // UCI_Elo testados (Stockfish aceita ≥ 1320)
+
+// Estudos de final pela tablebase (--mode endgame)
+pub const STUDY_MAX_PLIES: usize = 15;             // Plies da solução construída só com sondagens
+pub const TABLEBASE_WIN_CP: i32 = 10_000;          // Avaliação atribuída a uma vitória teórica
+
+// Relatório de fraquezas
+pub const TIME_PRESSURE_SECS: u32 = 60;            // Relógio (s) abaixo do qual o blunder conta como apuro de tempo
+pub const REPORT_TOP_WEAKNESSES: usize = 3;        // Quantidade de fraquezas resumidas por papel do jogador
+
 // Valores para configuração do Stockfish
 // Número de threads e tamanho de hash em MB usados no Stockfish
 pub const THREADS: u32 = 4;
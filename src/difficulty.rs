@@ -0,0 +1,74 @@
+// src/difficulty.rs
+// ---------------------------------------------------------------------------
+// Estimativa de dificuldade: reanalisa cada posição do resolvedor com
+// aprofundamento iterativo para achar a menor profundidade em que o motor
+// fixa o lance da solução, e combina com tamanho da linha, iscas (lances
+// errados preferidos em profundidade rasa), sacrifício e lance silencioso.
+// ---------------------------------------------------------------------------
+
+use anyhow::Result;
+use shakmaty::{Chess, Move, Position};
+
+use crate::{config, engine::Engine};
+
+/// O que a busca revelou sobre a linha de solução.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchProfile {
+    pub settle_depth: u8,       // maior profundidade de fixação entre as posições do resolvedor
+    pub decoys:       usize,    // lances distintos errados que o motor preferiu antes de fixar
+}
+
+/// Dificuldade estimada: pontuação 0–100 e rating aproximado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Difficulty {
+    pub score:  u8,
+    pub rating: i32,
+}
+
+/// Percorre a linha (`moves[0]` = primeiro lance do resolvedor) e mede cada
+/// posição em que o resolvedor joga.
+pub async fn search_profile(engine: &mut Engine, start: &Chess, moves: &[Move], depth: u8) -> Result<SearchProfile> {
+    let mut profile = SearchProfile::default();
+    let mut board   = start.clone();
+
+    for (i, mv) in moves.iter().enumerate() {
+        if i % 2 == 0 {
+            let by_depth = engine.best_by_depth(&board, depth).await?;
+            let (settle, decoys) = settle_depth(&by_depth, mv, depth);
+            profile.settle_depth = profile.settle_depth.max(settle);
+            profile.decoys      += decoys;
+        }
+        board.play_unchecked(mv);
+    }
+    Ok(profile)
+}
+
+/// Menor profundidade a partir da qual o melhor lance é sempre `solution`,
+/// e quantos lances diferentes foram preferidos antes disso.
+fn settle_depth(by_depth: &[(u8, Move)], solution: &Move, max_depth: u8) -> (u8, usize) {
+    let mut decoys: Vec<&Move> = Vec::new();
+    for (_, m) in by_depth {
+        if m != solution && !decoys.contains(&m) { decoys.push(m); }
+    }
+
+    let settle = match by_depth.iter().rposition(|(_, m)| m != solution) {
+        None                              => by_depth.first().map_or(1, |(d, _)| *d),
+        Some(i) if i + 1 < by_depth.len() => by_depth[i + 1].0,
+        Some(_)                           => max_depth.saturating_add(1),
+    };
+    (settle, decoys.len())
+}
+
+/// Combina os sinais num rating (arredondado a 50) e numa pontuação 0–100.
+pub fn estimate(profile: &SearchProfile, solver_moves: usize, sacrifice: bool, quiet: bool) -> Difficulty {
+    let points = config::RATING_PER_DEPTH * profile.settle_depth as i32
+        + config::RATING_PER_SOLVER_MOVE * solver_moves.saturating_sub(1) as i32
+        + config::RATING_PER_DECOY * profile.decoys as i32
+        + if sacrifice { config::RATING_SACRIFICE } else { 0 }
+        + if quiet { config::RATING_QUIET } else { 0 };
+
+    let rating = ((config::RATING_BASE + points + 25) / 50 * 50).clamp(config::RATING_MIN, config::RATING_MAX);
+    let span   = config::RATING_MAX - config::RATING_MIN;
+    let score  = ((rating - config::RATING_MIN) * 100 / span) as u8;
+    Difficulty { score, rating }
+}
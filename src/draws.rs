@@ -0,0 +1,123 @@
+// src/draws.rs
+// ---------------------------------------------------------------------------
+// Puzzles de salvação: a solução termina em afogamento, repetição tripla
+// (xeque perpétuo quando todos os lances do resolvedor no ciclo dão xeque)
+// ou numa posição que a tablebase confirma como empate.
+// ---------------------------------------------------------------------------
+
+use shakmaty::{
+    zobrist::{Zobrist64, ZobristHash},
+    Chess, EnPassantMode,
+};
+
+/// Como a linha de solução termina empatada.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawKind {
+    Stalemate,
+    Perpetual,
+    Repetition,
+    TablebaseDraw,
+}
+
+impl DrawKind {
+    /// Nome do tema (header `Themes`).
+    pub fn tag(self) -> &'static str {
+        match self {
+            DrawKind::Stalemate     => "stalemate",
+            DrawKind::Perpetual     => "perpetual",
+            DrawKind::Repetition    => "repetition",
+            DrawKind::TablebaseDraw => "tablebaseDraw",
+        }
+    }
+}
+
+/// Chave de repetição: posição + lado a mover + roques + en passant legal.
+pub fn position_key(pos: &Chess) -> u64 {
+    pos.zobrist_hash::<Zobrist64>(EnPassantMode::Legal).0
+}
+
+/// Histórico de posições (partida + linha atual) com os xeques do resolvedor.
+#[derive(Debug, Clone, Default)]
+pub struct RepetitionHistory {
+    keys:   Vec<u64>,
+    checks: Vec<(usize, bool)>,     // (índice em `keys` após o lance do resolvedor, deu xeque)
+}
+
+impl RepetitionHistory {
+    pub fn new(keys: Vec<u64>) -> Self { Self { keys, checks: Vec::new() } }
+
+    /// Registra a posição após um lance; `solver_check` só para lances do resolvedor.
+    pub fn push(&mut self, pos: &Chess, solver_check: Option<bool>) {
+        self.keys.push(position_key(pos));
+        if let Some(check) = solver_check { self.checks.push((self.keys.len() - 1, check)); }
+    }
+
+    /// Repetição tripla da última posição: perpétuo se todos os lances do
+    /// resolvedor desde a primeira ocorrência deram xeque.
+    pub fn threefold(&self) -> Option<DrawKind> {
+        let last  = *self.keys.last()?;
+        let seen: Vec<usize> = self.keys.iter().enumerate().filter(|(_, k)| **k == last).map(|(i, _)| i).collect();
+        if seen.len() < 3 { return None; }
+
+        let mut cycle = self.checks.iter().filter(|(i, _)| *i > seen[0]).peekable();
+        let perpetual = cycle.peek().is_some() && cycle.all(|(_, check)| *check);
+        Some(if perpetual { DrawKind::Perpetual } else { DrawKind::Repetition })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shakmaty::{fen::Fen, uci::UciMove, CastlingMode, Position};
+
+    /// Joga `ucis` a partir de `fen` como a árvore faz (resolvedor nos índices
+    /// pares) e devolve o veredito de cada ply.
+    fn replay(history: Vec<u64>, fen: &str, ucis: &[&str]) -> Vec<Option<DrawKind>> {
+        let mut pos: Chess = fen.parse::<Fen>().unwrap().into_position(CastlingMode::Standard).unwrap();
+        let mut reps = RepetitionHistory::new(history);
+        reps.push(&pos, None);
+        ucis.iter().enumerate().map(|(i, u)| {
+            let mv = u.parse::<UciMove>().unwrap().to_move(&pos).unwrap();
+            pos.play_unchecked(&mv);
+            reps.push(&pos, (i % 2 == 0).then(|| pos.is_check()));
+            reps.threefold()
+        }).collect()
+    }
+
+    #[test]
+    fn perpetual_check() {
+        // Qh5+ Kg8 Qe8+ Kh7 … até a terceira ocorrência de Qh5+
+        let line = ["d1h5", "h7g8", "h5e8", "g8h7", "e8h5", "h7g8", "h5e8", "g8h7", "e8h5"];
+        let verdicts = replay(Vec::new(), "8/6pk/8/8/8/8/8/3Q2K1 w - - 0 1", &line);
+        assert!(verdicts[..8].iter().all(Option::is_none), "{verdicts:?}");
+        assert_eq!(verdicts[8], Some(DrawKind::Perpetual));
+    }
+
+    #[test]
+    fn quiet_repetition_is_not_perpetual() {
+        // cavalo e rei vão e voltam sem xeque: a posição inicial volta duas vezes
+        let line = ["g1f3", "e8d8", "f3g1", "d8e8", "g1f3", "e8d8", "f3g1", "d8e8"];
+        let verdicts = replay(Vec::new(), "4k3/8/8/8/8/8/8/4K1N1 w - - 0 1", &line);
+        assert!(verdicts[..7].iter().all(Option::is_none), "{verdicts:?}");
+        assert_eq!(verdicts[7], Some(DrawKind::Repetition));
+    }
+
+    #[test]
+    fn one_quiet_move_breaks_the_perpetual() {
+        // Qe8+ alterna com Qd7, que não dá xeque: repetição comum
+        let line = ["h5e8", "g8h7", "e8d7", "h7g8", "d7e8", "g8h7", "e8d7", "h7g8", "d7e8"];
+        let verdicts = replay(Vec::new(), "6k1/6p1/8/7Q/8/8/8/6K1 w - - 0 1", &line);
+        assert_eq!(verdicts[8], Some(DrawKind::Repetition), "{verdicts:?}");
+    }
+
+    #[test]
+    fn game_history_counts() {
+        // a posição inicial já apareceu duas vezes na partida
+        let fen = "4k3/8/8/8/8/8/8/4K1N1 w - - 0 1";
+        let pos: Chess = fen.parse::<Fen>().unwrap().into_position(CastlingMode::Standard).unwrap();
+        let key = position_key(&pos);
+        let mut reps = RepetitionHistory::new(vec![key, 1, key]);
+        reps.push(&pos, None);
+        assert_eq!(reps.threefold(), Some(DrawKind::Repetition));
+    }
+}
@@ -0,0 +1,116 @@
+// src/eco.rs
+// ---------------------------------------------------------------------------
+// Classificação ECO por tabela embutida (volumes a.tsv–e.tsv do
+// lichess-org/chess-openings, CC0: código, nome, lances; ver data/eco e
+// fetch_eco.sh). A busca é por EPD, então reconhece transposições; vale a
+// última posição da partida encontrada na tabela.
+// ---------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use shakmaty::{fen::Epd, san::San, Chess, EnPassantMode, Position};
+
+/// Um arquivo por volume ECO, cada um com sua linha de cabeçalho.
+static ECO_VOLUMES: [&str; 5] = [
+    include_str!("../data/eco/a.tsv"),
+    include_str!("../data/eco/b.tsv"),
+    include_str!("../data/eco/c.tsv"),
+    include_str!("../data/eco/d.tsv"),
+    include_str!("../data/eco/e.tsv"),
+];
+
+/// Linhas de dados de todos os volumes, sem os cabeçalhos.
+fn table_lines() -> impl Iterator<Item = &'static str> {
+    ECO_VOLUMES.iter().flat_map(|v| v.lines().skip(1))
+}
+
+/// Entrada da tabela: código ECO e nome ("Família: Variante, Subvariante").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Opening {
+    pub eco:  &'static str,
+    pub name: &'static str,
+}
+
+impl Opening {
+    /// Família da abertura: o nome até ':' ("Sicilian Defense").
+    pub fn family(&self) -> &'static str { family(self.name) }
+
+    /// Variante: o resto do nome, se houver ("Najdorf Variation, English Attack").
+    pub fn variation(&self) -> Option<&'static str> {
+        self.name.split_once(':').map(|(_, v)| v.trim())
+    }
+}
+
+/// Família a partir de um nome de abertura qualquer (tabela ou header `Opening`).
+pub fn family(name: &str) -> &str {
+    name.split(':').next().unwrap_or(name).trim()
+}
+
+lazy_static::lazy_static! {
+    static ref TABLE: HashMap<String, Opening> = build_table();
+}
+
+fn epd(pos: &Chess) -> String {
+    Epd::from_position(pos.clone(), EnPassantMode::Legal).to_string()
+}
+
+/// EPD da posição final de cada linha → entrada (a primeira vence).
+fn build_table() -> HashMap<String, Opening> {
+    let mut table = HashMap::new();
+    for line in table_lines() {
+        let mut cols = line.split('\t');
+        let (Some(eco), Some(name), Some(moves)) = (cols.next(), cols.next(), cols.next()) else { continue };
+        if let Some(pos) = replay_movetext(moves) {
+            table.entry(epd(&pos)).or_insert(Opening { eco, name });
+        }
+    }
+    table
+}
+
+/// "1. e4 e5 2. Nf3" a partir da posição inicial; `None` se algum lance é ilegal.
+fn replay_movetext(moves: &str) -> Option<Chess> {
+    moves
+        .split_whitespace()
+        .filter(|tok| !tok.ends_with('.'))
+        .try_fold(Chess::default(), |pos, tok| {
+            let mv = tok.parse::<San>().ok()?.to_move(&pos).ok()?;
+            pos.play(&mv).ok()
+        })
+}
+
+/// Abertura da posição, se ela está na tabela.
+pub fn lookup(pos: &Chess) -> Option<&'static Opening> {
+    TABLE.get(&epd(pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after(moves: &str) -> Chess { replay_movetext(moves).unwrap() }
+
+    #[test]
+    fn every_table_line_is_legal() {
+        for line in table_lines() {
+            let moves = line.split('\t').nth(2).unwrap_or_default();
+            assert!(replay_movetext(moves).is_some(), "{line}");
+        }
+    }
+
+    #[test]
+    fn transpositions_are_recognized() {
+        // 1.c4 Nf6 2.Nc3 g6 3.d4 Bg7 chega ao King's Indian por outra ordem
+        let pos = after("1. c4 Nf6 2. Nc3 g6 3. d4 Bg7");
+        let opening = lookup(&pos).unwrap();
+        assert_eq!(opening.eco, "E61");
+        assert_eq!(opening.family(), "King's Indian Defense");
+        assert_eq!(opening.variation(), None);
+    }
+
+    #[test]
+    fn name_splits_into_family_and_variation() {
+        let opening = lookup(&after("1. e4 c5 2. Nf3 d6 3. d4 cxd4 4. Nxd4 Nf6 5. Nc3 a6 6. Be3")).unwrap();
+        assert_eq!(opening.family(), "Sicilian Defense");
+        assert_eq!(opening.variation(), Some("Najdorf Variation, English Attack"));
+    }
+}
@@ -0,0 +1,324 @@
+// src/endgame.rs
+// ---------------------------------------------------------------------------
+// Estudos de final pela tablebase: posições (≤ peças Syzygy carregadas) em
+// que só um lance preserva o resultado teórico (vitória ou empate). A solução
+// inteira vem de sondagens WDL/DTZ — nenhuma chamada ao motor.
+// Também classifica o material de finais: assinatura ("KRPvKR") e família.
+// ---------------------------------------------------------------------------
+
+use shakmaty::{Bitboard, Board, Chess, Color, Move, Position, Role};
+use shakmaty_syzygy::{Dtz, MaybeRounded, Tablebase, Wdl};
+
+use crate::{
+    builder::PuzzleSeq,
+    candidates::{CandidateKind, PuzzleCandidate},
+    config,
+    draws::DrawKind,
+    utils::MoveRecord,
+};
+
+/// Resultado com a regra dos 50 lances: 1 vence, 0 empata, ‑1 perde.
+fn outcome(wdl: Wdl) -> i8 {
+    match wdl {
+        Wdl::Win                                   => 1,
+        Wdl::CursedWin | Wdl::Draw | Wdl::BlessedLoss => 0,
+        Wdl::Loss                                  => -1,
+    }
+}
+
+/// Resultado para o lado a mover, considerando o contador de meios‑lances;
+/// `None` se o arredondamento do DTZ deixa o resultado ambíguo. Posições
+/// terminais não vão à tablebase.
+fn probe(tb: &Tablebase<Chess>, pos: &Chess) -> Option<i8> {
+    if pos.is_checkmate() { return Some(-1); }
+    if pos.is_stalemate() || pos.is_insufficient_material() { return Some(0); }
+    if pos.board().occupied().count() > tb.max_pieces() { return None; }
+    tb.probe_wdl(pos).ok()?.unambiguous().map(outcome)
+}
+
+/// Distância até o próximo lance que zera o contador (em plies, absoluta).
+fn dtz_len(tb: &Tablebase<Chess>, pos: &Chess) -> i32 {
+    match tb.probe_dtz(pos) {
+        Ok(MaybeRounded::Precise(Dtz(v)) | MaybeRounded::Rounded(Dtz(v))) => v.abs(),
+        Err(_)                                                           => 0,
+    }
+}
+
+/// Lance único que preserva o resultado teórico (vitória ou empate) e esse
+/// resultado. `None` se a posição já está perdida, se há mais de um lance
+/// que segura ou se algum lance sai do alcance da tablebase ou tem
+/// resultado ambíguo (regra dos 50 lances).
+pub fn only_move(tb: &Tablebase<Chess>, pos: &Chess) -> Option<(Move, i8)> {
+    let legal = pos.legal_moves();
+    if legal.len() < 2 { return None; }
+
+    let scored: Vec<(Move, i8)> = legal
+        .into_iter()
+        .map(|mv| {
+            let child = pos.clone().play(&mv).ok()?;
+            Some((mv, -probe(tb, &child)?))
+        })
+        .collect::<Option<_>>()?;
+    let best = scored.iter().map(|(_, o)| *o).max()?;
+    if best < 0 { return None; }
+
+    let mut keep = scored.into_iter().filter(|(_, o)| *o == best);
+    let first = keep.next()?;
+    keep.next().is_none().then_some(first)
+}
+
+/// Defesa mais resistente: melhor resultado para quem defende; entre
+/// equivalentes, a que mantém o lance único do resolvedor e, depois, a que
+/// adia mais a conversão (maior DTZ).
+fn toughest_reply(tb: &Tablebase<Chess>, pos: &Chess) -> Option<Move> {
+    pos.legal_moves()
+        .into_iter()
+        .filter_map(|mv| {
+            let child = pos.clone().play(&mv).ok()?;
+            let held  = -probe(tb, &child)?;
+            let keeps = only_move(tb, &child).is_some();
+            Some(((held, keeps, dtz_len(tb, &child)), mv))
+        })
+        .max_by_key(|(rank, _)| *rank)
+        .map(|(_, mv)| mv)
+}
+
+/// Avaliação (ponto de vista das brancas) de um resultado teórico.
+fn outcome_cp(outcome: i8, turn: Color) -> i32 {
+    let sign = if turn == Color::White { 1 } else { -1 };
+    sign * i32::from(outcome) * config::TABLEBASE_WIN_CP
+}
+
+/// Linha de solução só com a tablebase: lances únicos do resolvedor e a
+/// defesa mais resistente, até o lance deixar de ser único. Como nos puzzles
+/// do motor, exige ao menos `SOLVER_MIN_MOVES` lances do resolvedor.
+pub fn study_line(tb: &Tablebase<Chess>, start: &Chess) -> Option<PuzzleSeq> {
+    let solver = start.turn();
+    let mut board  = start.clone();
+    let mut moves  = Vec::new();
+    let mut target = None;
+
+    while moves.len() < config::STUDY_MAX_PLIES {
+        let Some((mv, held)) = only_move(tb, &board) else { break };
+        target.get_or_insert(held);
+        board.play_unchecked(&mv);
+        moves.push(mv);
+        if board.is_game_over() { break; }
+
+        let Some(reply) = toughest_reply(tb, &board) else { break };
+        board.play_unchecked(&reply);
+        moves.push(reply);
+    }
+    let target = target?;
+
+    // a linha termina num lance do resolvedor
+    if moves.len().is_multiple_of(2) {
+        moves.pop();
+        board = start.clone();
+        for mv in &moves { board.play_unchecked(mv); }
+    }
+
+    let solver_moves = moves.len().div_ceil(2);
+    if solver_moves < config::SOLVER_MIN_MOVES as usize { return None; }
+    let end_outcome  = if board.turn() == solver { target } else { -target };
+    Some(PuzzleSeq {
+        unique:      vec![true; solver_moves],
+        variations:  Vec::new(),
+        final_cp:    outcome_cp(end_outcome, board.turn()),
+        is_mate:     board.is_checkmate(),
+        comments:    Vec::new(),
+        evals:       vec![None; moves.len()],
+        glyphs:      Vec::new(),
+        refutations: Vec::new(),
+        draw:        (target == 0).then_some(DrawKind::TablebaseDraw),
+        moves,
+    })
+}
+
+/// Posições das partidas com lance único pela tablebase. Depois de um estudo,
+/// pula `STUDY_MAX_PLIES` plies da mesma partida (a solução já os cobre).
+pub fn collect_studies<I>(tb: &Tablebase<Chess>, games: I) -> Vec<(PuzzleCandidate, Vec<(String, String)>)>
+where
+    I: IntoIterator<Item = MoveRecord>,
+{
+    let mut pool = Vec::new();
+    let mut last: Option<(Chess, Move, u32)> = None;   // lance anterior (pré, lance, nº)
+    let mut skip_until = 0u32;
+
+    for rec in games {
+        if rec.move_idx == 1 { last = None; skip_until = 0; }
+
+        if let (Some((pre, prev_mv, prev_no)), true) = (&last, rec.move_idx > skip_until) {
+            if let Some((_, held)) = only_move(tb, &rec.board) {
+                let cp = outcome_cp(held, rec.board.turn());
+                pool.push((
+                    PuzzleCandidate {
+                        board_pre_blunder : pre.clone(),
+                        board_post_blunder: rec.board.clone(),
+                        blunder_move      : prev_mv.clone(),
+                        solver_color      : rec.board.turn(),
+                        pre_cp            : cp,
+                        post_cp           : cp,
+                        move_number       : *prev_no,
+                        played_reply      : Some(rec.mv.clone()),
+                        blunder_clock     : None,
+                        kind              : CandidateKind::Study,
+                        best_alternative  : None,
+                        history           : Vec::new(),
+                    },
+                    rec.headers.clone(),
+                ));
+                skip_until = rec.move_idx + config::STUDY_MAX_PLIES as u32;
+            }
+        }
+        last = Some((rec.board, rec.mv, rec.move_idx));
+    }
+    pool
+}
+
+/// Assinatura material do final, lado mais forte primeiro: "KRPvKR", "KBNvK".
+pub fn material_signature(board: &Board) -> String {
+    let side = |color: Color| -> (i32, String) {
+        let mut value = 0;
+        let mut sig   = String::from("K");
+        for (role, ch, v) in [(Role::Queen, 'Q', 9), (Role::Rook, 'R', 5), (Role::Bishop, 'B', 3), (Role::Knight, 'N', 3), (Role::Pawn, 'P', 1)] {
+            let n = (board.by_role(role) & board.by_color(color)).count();
+            value += v * n as i32;
+            sig.extend(std::iter::repeat_n(ch, n));
+        }
+        (value, sig)
+    };
+    let (white, black) = (side(Color::White), side(Color::Black));
+    let (strong, weak) = if black.0 > white.0 { (black, white) } else { (white, black) };
+    format!("{}v{}", strong.1, weak.1)
+}
+
+/// Família do final pelo material (peões à parte), para treinos por tipo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndgameCategory {
+    Pawn,              // só reis e peões
+    Knight,            // só cavalos
+    Bishop,            // só bispos (mesma cor ou mais de um)
+    OppositeBishops,   // um bispo de cada lado, em cores opostas
+    MinorPiece,        // bispos e cavalos
+    Rook,              // só torres
+    RookMinor,         // torres e peças menores
+    Queen,             // só damas
+    QueenRook,         // damas e torres
+    Mixed,             // dama com peças menores e demais combinações
+}
+
+impl EndgameCategory {
+    /// Nome do tema (header `Themes`).
+    pub fn tag(self) -> &'static str {
+        match self {
+            EndgameCategory::Pawn            => "pawnEndgame",
+            EndgameCategory::Knight          => "knightEndgame",
+            EndgameCategory::Bishop          => "bishopEndgame",
+            EndgameCategory::OppositeBishops => "oppositeBishops",
+            EndgameCategory::MinorPiece      => "minorPieceEndgame",
+            EndgameCategory::Rook            => "rookEndgame",
+            EndgameCategory::RookMinor       => "rookMinorEndgame",
+            EndgameCategory::Queen           => "queenEndgame",
+            EndgameCategory::QueenRook       => "queenRookEndgame",
+            EndgameCategory::Mixed           => "mixedEndgame",
+        }
+    }
+}
+
+/// Família do final a partir das peças (exceto reis e peões) dos dois lados.
+pub fn classify_endgame(board: &Board) -> EndgameCategory {
+    use EndgameCategory::*;
+    let n = |role: Role| board.by_role(role).count();
+    let (queens, rooks, bishops, knights) = (n(Role::Queen), n(Role::Rook), n(Role::Bishop), n(Role::Knight));
+
+    match (queens > 0, rooks > 0, bishops + knights > 0) {
+        (false, false, false) => Pawn,
+        (false, false, true)  => match (bishops, knights) {
+            (_, 0) if opposite_bishops(board) => OppositeBishops,
+            (_, 0)                            => Bishop,
+            (0, _)                            => Knight,
+            _                                 => MinorPiece,
+        },
+        (false, true, false) => Rook,
+        (false, true, true)  => RookMinor,
+        (true, false, false) => Queen,
+        (true, true, false)  => QueenRook,
+        (true, _, true)      => Mixed,
+    }
+}
+
+/// Um bispo de cada lado, em casas de cores diferentes.
+fn opposite_bishops(board: &Board) -> bool {
+    let side = |color: Color| board.by_role(Role::Bishop) & board.by_color(color);
+    let (white, black) = (side(Color::White), side(Color::Black));
+    white.count() == 1 && black.count() == 1
+        && (white & Bitboard::LIGHT_SQUARES).any() != (black & Bitboard::LIGHT_SQUARES).any()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shakmaty::{fen::Fen, uci::UciMove, CastlingMode};
+
+    use crate::engine::load_syzygy;
+
+    fn position(fen: &str) -> Chess {
+        fen.parse::<Fen>().unwrap().into_position(CastlingMode::Standard).unwrap()
+    }
+
+    fn board(fen: &str) -> Board { position(fen).board().clone() }
+
+    /// Tablebases de SYZYGY_PATHS com ao menos 4 peças; sem elas os testes
+    /// de sondagem não têm o que verificar e passam direto.
+    fn tablebase() -> Option<Tablebase<Chess>> {
+        load_syzygy().filter(|tb| tb.max_pieces() >= 4)
+    }
+
+    #[test]
+    fn unique_winning_move() {
+        let Some(tb) = tablebase() else { return };
+        // só Rxb4 leva a KRvK; qualquer outro lance fica em KRvKR empatado
+        let pos = position("8/8/8/3k4/1r6/8/8/1R4K1 w - - 0 1");
+        let (mv, held) = only_move(&tb, &pos).unwrap();
+        assert_eq!(mv, "b1b4".parse::<UciMove>().unwrap().to_move(&pos).unwrap());
+        assert_eq!(held, 1);
+        // depois de Rxb4 há vários lances que vencem: um lance só não é estudo
+        assert!(study_line(&tb, &pos).is_none());
+    }
+
+    #[test]
+    fn drawn_position_has_no_study() {
+        let Some(tb) = tablebase() else { return };
+        // KRvKR sem tática: muitos lances seguram o empate
+        let pos = position("8/8/8/3k4/8/5r2/8/1R4K1 w - - 0 1");
+        assert!(only_move(&tb, &pos).is_none());
+        assert!(study_line(&tb, &pos).is_none());
+    }
+
+    #[test]
+    fn known_endgames() {
+        let cases = [
+            ("8/5k2/8/3r4/8/3RP3/5K2/8 w - - 0 1",      "KRPvKR", EndgameCategory::Rook),
+            ("8/8/8/4k3/8/8/8/2BNK3 w - - 0 1",         "KBNvK",  EndgameCategory::MinorPiece),
+            ("8/5kp1/8/8/8/8/4KP2/8 w - - 0 1",         "KPvKP",  EndgameCategory::Pawn),
+            ("8/5k2/4b3/8/8/4B3/5K2/8 w - - 0 1",       "KBvKB",  EndgameCategory::OppositeBishops),
+            ("8/5k2/3b4/8/8/4B3/5K2/8 w - - 0 1",       "KBvKB",  EndgameCategory::Bishop),
+            ("8/4nk2/8/8/8/8/3NPK2/8 w - - 0 1",        "KNPvKN", EndgameCategory::Knight),
+            ("8/5k2/8/3n4/8/3R4/5K2/8 w - - 0 1",       "KRvKN",  EndgameCategory::RookMinor),
+            ("8/1p3k2/8/3r4/8/3Q4/5K2/8 w - - 0 1",     "KQvKRP", EndgameCategory::QueenRook),
+            ("8/5kq1/8/8/8/3Q4/5K2/8 w - - 0 1",        "KQvKQ",  EndgameCategory::Queen),
+            ("8/5kn1/8/8/8/3Q4/5K2/8 w - - 0 1",        "KQvKN",  EndgameCategory::Mixed),
+        ];
+        for (fen, signature, category) in cases {
+            let b = board(fen);
+            assert_eq!(material_signature(&b), signature, "{fen}");
+            assert_eq!(classify_endgame(&b), category, "{fen}");
+        }
+    }
+
+    #[test]
+    fn stronger_side_comes_first() {
+        assert_eq!(material_signature(&board("8/5k2/8/3r4/8/8/4PK2/8 w - - 0 1")), "KRvKP");
+    }
+}
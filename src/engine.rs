@@ -63,6 +63,7 @@ pub struct Engine {
     timeout_ms:  u64,
     current_mpv: u32,
     tb:          Option<Tablebase<Chess>>,
+    limited:     bool,                  // UCI_LimitStrength ativo: sem atalho da tablebase
     start:       Instant,
 }
 
@@ -97,6 +98,7 @@ impl Engine {
             timeout_ms: DEFAULT_TIMEOUT_MS,
             current_mpv: 1,
             tb: load_syzygy(),
+            limited: false,
             start: Instant::now(),
         })
     }
@@ -148,11 +150,49 @@ impl Engine {
         Ok(())
     }
 
+    // ---------- opções ----------
+    pub async fn set_option(&mut self, name: &str, value: &str) -> Result<()> {
+        self.send(SetOption { name: Cow::Owned(name.into()), value: Some(Cow::Owned(value.into())) }).await?;
+        self.ready().await
+    }
+
+    /// Enfraquece o motor para `UCI_Elo` (None → força total).
+    pub async fn limit_strength(&mut self, elo: Option<u32>) -> Result<()> {
+        self.set_option("UCI_LimitStrength", if elo.is_some() { "true" } else { "false" }).await?;
+        if let Some(elo) = elo { self.set_option("UCI_Elo", &elo.to_string()).await?; }
+        self.limited = elo.is_some();
+        Ok(())
+    }
+
+    /// Tablebase Syzygy carregada (SYZYGY_PATHS), se houver.
+    pub fn tablebase(&self) -> Option<&Tablebase<Chess>> { self.tb.as_ref() }
+
+    /// WDL da tablebase para o lado a mover (≤ peças da tablebase carregada),
+    /// com o contador de meios‑lances; `None` se ambíguo pelo arredondamento do DTZ.
+    pub fn tablebase_wdl(&self, board: &Chess) -> Option<Wdl> {
+        let tb = self.tb.as_ref()?;
+        if board.board().occupied().count() > tb.max_pieces() { return None; }
+        tb.probe_wdl(board).ok()?.unambiguous()
+    }
+
     // ---------- análise ----------
     pub async fn analyze(&mut self, board: &Chess, depth: u8, mpv: u32) -> Result<Vec<AnalysisInfo>> {
-        if let Some(ref tb) = self.tb {
+        Ok(self.search(board, depth, mpv).await?.0)
+    }
+
+    /// Linhas da MultiPV e o `bestmove` final da busca. Com força limitada
+    /// (UCI_LimitStrength) só o `bestmove` traz a escolha enfraquecida; a PV
+    /// impressa continua sendo a de força total. A tablebase só é consultada
+    /// em força total; senão todo nível enfraquecido "acharia" os finais.
+    async fn search(&mut self, board: &Chess, depth: u8, mpv: u32) -> Result<(Vec<AnalysisInfo>, Option<NormalBestMove>)> {
+        if let Some(tb) = self.tb.as_ref().filter(|_| !self.limited) {
             if board.board().occupied().into_iter().count() <= 7 {
-                return Ok(vec![probe_tablebase(board, tb)?]);
+                let info = probe_tablebase(board, tb)?;
+                let best = info.pv.first().map(|m| NormalBestMove {
+                    r#move: UciMove::from_move(m, CastlingMode::Standard),
+                    ponder: None,
+                });
+                return Ok((vec![info], best));
             }
         }
 
@@ -164,7 +204,7 @@ impl Engine {
         let cb = map.clone();
 
         let limit = Duration::from_millis(self.timeout_ms * ANALYSIS_FACTOR * depth as u64);
-        timeout(limit, self.inner.go_async(&go, move |info: Info| {
+        let best  = timeout(limit, self.inner.go_async(&go, move |info: Info| {
             if let (Some(id), Some(_)) = (info.multi_pv, info.score.as_ref()) {
                 if !info.pv.is_empty() {
                     cb.lock().unwrap().insert(id as u32, convert_info(&info, board.turn(), board));
@@ -176,7 +216,7 @@ impl Engine {
         let mut lines: Vec<_> = Arc::try_unwrap(map).unwrap().into_inner().unwrap().into_values().collect();
         let sign = if board.turn() == Color::White { -1 } else { 1 };
         lines.sort_by_key(|i| i.score.as_ref().map_or(i64::MIN, |s| sign * Self::key(s)));
-        Ok(lines)
+        Ok((lines, best.take_normal()))
     }
 
     // ---------- wrappers FEN ----------
@@ -185,15 +225,38 @@ impl Engine {
         self.analyze(&pos, depth, mpv).await
     }
 
+    /// Lance do `bestmove` do motor (respeita UCI_LimitStrength/UCI_Elo).
     pub async fn best_move(&mut self, board: &Chess, depth: u8) -> Result<Option<NormalBestMove>> {
-        let mv_opt = self.analyze(board, depth, 1).await?
-            .pop()
-            .and_then(|i| i.pv.first().cloned());
-
-        Ok(mv_opt.map(|m| NormalBestMove {
-            r#move:  UciMove::from_move(&m, CastlingMode::Standard),
-            ponder: None,
-        }))
+        Ok(self.search(board, depth, 1).await?.1)
+    }
+
+    /// Primeiro lance da PV principal a cada profundidade do aprofundamento
+    /// iterativo (uma única busca); tablebase → apenas profundidade 1.
+    pub async fn best_by_depth(&mut self, board: &Chess, depth: u8) -> Result<Vec<(u8, ShakMove)>> {
+        if let Some(ref tb) = self.tb {
+            if board.board().occupied().into_iter().count() <= 7 {
+                return Ok(probe_tablebase(board, tb)?.pv.first().map(|m| vec![(1, m.clone())]).unwrap_or_default());
+            }
+        }
+
+        self.set_position(board).await?;
+        self.ensure_mpv(1).await?;
+
+        let go = Go { depth: Some(depth as usize), ..Default::default() };
+        let map: Arc<Mutex<HashMap<u8, ShakMove>>> = Arc::new(Mutex::new(HashMap::new()));
+        let cb = map.clone();
+
+        let limit = Duration::from_millis(self.timeout_ms * ANALYSIS_FACTOR * depth as u64);
+        timeout(limit, self.inner.go_async(&go, move |info: Info| {
+            if let (Some(d), Some(first), None | Some(1)) = (info.depth, info.pv.first(), info.multi_pv) {
+                if let Ok(mv) = first.to_move(board) { cb.lock().unwrap().insert(d.depth as u8, mv); }
+            }
+            ready(())
+        })).await??;
+
+        let mut lines: Vec<_> = Arc::try_unwrap(map).unwrap().into_inner().unwrap().into_iter().collect();
+        lines.sort_by_key(|(d, _)| *d);
+        Ok(lines)
     }
 
     pub async fn best_move_fen(&mut self, fen: &str, depth: u8) -> Result<Option<NormalBestMove>> {
@@ -220,7 +283,8 @@ impl Drop for Engine { fn drop(&mut self) { let _ = self.child.start_kill(); } }
 // ---------------------------------------------------------------------------
 // Helpers Syzygy
 // ---------------------------------------------------------------------------
-fn load_syzygy() -> Option<Tablebase<Chess>> {
+/// Tablebases dos diretórios em SYZYGY_PATHS; `None` se nenhuma carregou.
+pub fn load_syzygy() -> Option<Tablebase<Chess>> {
     let paths = env::var("SYZYGY_PATHS").ok()?;
     let mut tb = Tablebase::new();
     for dir in env::split_paths(&paths) { let _ = tb.add_directory(&dir); }
@@ -266,6 +330,12 @@ fn convert_info(src: &Info, turn: Color, board: &Chess) -> AnalysisInfo {
     let depth    = src.depth.map(|d| d.depth as u8);
     let seldepth = src.depth.and_then(|d| d.seldepth.map(|s| s as u8));
     let nodes    = src.nodes.map(|n| n as u64);
-    let pv       = src.pv.iter().filter_map(|uci| uci.to_move(board).ok()).collect();
+    // cada lance da PV é convertido na posição após os anteriores
+    let mut pos  = board.clone();
+    let pv       = src.pv.iter().map_while(|uci| {
+        let mv = uci.to_move(&pos).ok()?;
+        pos.play_unchecked(&mv);
+        Some(mv)
+    }).collect();
     AnalysisInfo { score, depth, seldepth, nodes, pv, origin: AnalysisOrigin::Engine }
 }
@@ -8,7 +8,27 @@ use std::path::Path;
 
 // Bibliotecas externas
 use anyhow::{Context, Result};
+use clap::ValueEnum;
 use log::{debug, info, warn, trace};
+use serde::Serialize;
+
+/// Como exportar as refutações dos erros tentadores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RefutationExport {
+    /// Não exporta
+    None,
+    /// Como variantes no PGN do puzzle
+    Variations,
+    /// Em arquivo JSON Lines ao lado do PGN
+    Json,
+}
+
+/// Opções de saída que afetam o PGN de cada puzzle.
+#[derive(Debug, Clone, Copy)]
+pub struct ExportOptions {
+    pub annotate:    bool,                // [%eval], "??"/"!" e comentários de refutação
+    pub refutations: RefutationExport,
+}
 
 /// Abre o arquivo de saída para escrita dos puzzles.
 /// Se `resume` for true e o arquivo já existir, abre em modo de acréscimo (append) para continuar escrevendo.
@@ -68,3 +88,10 @@ pub fn export_puzzle(pgn_string: &str, output: &mut dyn Write) -> Result<()> {
     debug!("export_puzzle: puzzle exportado com sucesso");
     Ok(())
 }
+
+/// Exporta um registro como uma linha JSON (JSON Lines)
+pub fn export_json_line<T: Serialize>(value: &T, output: &mut dyn Write) -> Result<()> {
+    let line = serde_json::to_string(value).context("Falha ao serializar registro JSON")?;
+    writeln!(output, "{}", line).context("Falha ao escrever registro JSON")?;
+    Ok(())
+}
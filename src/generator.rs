@@ -4,20 +4,40 @@
 // ---------------------------------------------------------------------------
 
 use std::{path::PathBuf, time::Instant};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use log::info;
 use shakmaty::Chess;
 
 use crate::{
-    builder::{create_puzzle_tree, process_puzzle},
-    candidates::CandidateContext,
-    exporter::export_puzzle,
+    analysis::avoidable_alternative,
+    book::Book,
+    builder::{create_puzzle_tree, process_puzzle, TreeOptions},
+    candidates::{CandidateContext, CandidateKind, ExtractionMode},
+    difficulty::search_profile,
+    endgame::{collect_studies, study_line},
+    human::human_check,
+    exporter::{export_json_line, export_puzzle, open_output_file, ExportOptions, RefutationExport},
     resume::{initialize_resume, update_resume_data},
     engine::Engine,
+    player::PlayerFilter,
+    see::TrivialPolicy,
     utils::{iterate_games, prepare_engine, prepare_output_file, DepthSet},
 };
 
-pub struct GeneratorArgs { pub base_depth: u8, pub resume: bool, pub verbose: bool }
+pub struct GeneratorArgs {
+    pub base_depth: u8,
+    pub resume:     bool,
+    pub verbose:    bool,
+    pub player:     Option<PlayerFilter>,
+    pub mode:       ExtractionMode,
+    pub book:       Option<PathBuf>,
+    pub sacrifices_only: bool,
+    pub trivial:    TrivialPolicy,
+    pub themes:     Vec<String>,
+    pub human_check: bool,
+    pub tree:       TreeOptions,
+    pub export:     ExportOptions,
+}
 pub struct GenerateResult { puzzles: u64 }
 impl GenerateResult { pub fn total(&self) -> u64 { self.puzzles } }
 
@@ -29,33 +49,138 @@ pub async fn generate_puzzles(
     let t0 = Instant::now();
     let (out_path, mut out_file) = prepare_output_file(input, output, args.resume)?;
     let (depths, mut engine)    = prepare_engine(args.base_depth).await?;
-    let (_, _, stats)           = initialize_resume(
+    let (_, _, mut stats)       = initialize_resume(
         input,
         out_path.parent().unwrap().to_str().unwrap(),
         args.resume,
     )?;
 
+    // conjunto separado de puzzles fáceis (peça pendurada / recaptura)
+    let mut easy_file = match args.trivial {
+        TrivialPolicy::Split => {
+            let stem = out_path.file_stem().and_then(|s| s.to_str()).unwrap_or("puzzles");
+            Some(open_output_file(&out_path.with_file_name(format!("{stem}_easy.pgn")), args.resume)?)
+        }
+        _ => None,
+    };
+
+    // refutações dos erros tentadores em JSON Lines
+    let mut refutation_file = match args.export.refutations {
+        RefutationExport::Json => {
+            let stem = out_path.file_stem().and_then(|s| s.to_str()).unwrap_or("puzzles");
+            Some(open_output_file(&out_path.with_file_name(format!("{stem}_refutations.jsonl")), args.resume)?)
+        }
+        _ => None,
+    };
+
+    let book = args.book.as_deref().map(Book::open).transpose()?;
+    if args.mode == ExtractionMode::Traps && book.is_none() {
+        bail!("modo traps requer um livro de aberturas (--book)");
+    }
+
     let t1 = Instant::now();
-    let mut ctx  = CandidateContext::new(&mut engine, None);
-    let pool     = ctx.collect_candidates(Chess::default(), iterate_games(input)?, &depths).await?;
+    let player   = args.player.as_ref();
+    let games    = iterate_games(input)?.filter(|rec| player.is_none_or(|p| p.plays_in(&rec.headers)));
+    let mut pool = match (args.mode, engine.tablebase()) {
+        (ExtractionMode::Endgame, Some(tb)) => collect_studies(tb, games),
+        (ExtractionMode::Endgame, None)     => bail!("modo endgame requer tablebases Syzygy (SYZYGY_PATHS)"),
+        _ => {
+            let mut ctx = CandidateContext::new(&mut engine, None, args.mode, book.as_ref());
+            ctx.collect_candidates(Chess::default(), games, &depths).await?
+        }
+    };
+    if let Some(p) = player {
+        pool.retain(|(cand, hdrs)| p.accepts(cand, hdrs));
+    }
     info!("fase‑1 concluída → {} candidatos em {:.2?}", pool.len(), t1.elapsed());
 
     let mut total = 0u64;
-    for (cand, hdrs) in pool {
-        if let Some(seq) = create_puzzle_tree(
-            &mut engine,
-            &cand.board_post_blunder,
-            cand.solver_color,
-            cand.pre_cp,
-            &depths,
-        )
-        .await?
-        {
-            if let Ok(pgn) = process_puzzle(&cand, &seq, &hdrs) {
-                if export_puzzle(&pgn, &mut out_file).is_ok() {
-                    total += 1;
+    let mut easy_total = 0u64;              // puzzles triviais do conjunto fácil (--trivial split)
+    for (mut cand, hdrs) in pool {
+        // blunder só vira puzzle se havia alternativa claramente melhor
+        let study = cand.kind == CandidateKind::Study;
+        if !study && cand.kind != CandidateKind::OnlyMove {
+            let alt = avoidable_alternative(
+                &mut engine, &cand.board_pre_blunder, &cand.blunder_move, cand.post_cp, depths.scan,
+            ).await?;
+            if alt.is_none() {
+                stats.add_rejected("blunder inevitável", 1);
+                continue;
+            }
+            cand.best_alternative = alt;
+        }
+
+        // estudos de final: solução só com a tablebase, sem motor
+        let seq = match study {
+            true  => engine.tablebase().and_then(|tb| study_line(tb, &cand.board_post_blunder)),
+            false => create_puzzle_tree(
+                &mut engine,
+                &cand.board_post_blunder,
+                cand.solver_color,
+                cand.pre_cp,
+                &depths,
+                args.tree,
+                &cand.history,
+            )
+            .await?,
+        };
+        let Some(seq) = seq else { continue };
+
+        // classificação e filtros antes da busca iterativa e do motor
+        // enfraquecido: só os puzzles que sobrevivem pagam esse custo
+        let Ok(draft) = process_puzzle(&cand, &seq, &hdrs, None, None, args.export) else { continue };
+        if args.sacrifices_only && !draft.has_theme("sacrifice") {
+            stats.add_rejected("sem sacrifício", 1);
+            continue;
+        }
+        if !draft.has_all_themes(&args.themes) {
+            stats.add_rejected("temas ausentes", 1);
+            continue;
+        }
+        let easy = match (draft.trivial, args.trivial) {
+            (Some(kind), TrivialPolicy::Reject) => {
+                stats.add_rejected(&format!("trivial: {}", kind.tag()), 1);
+                continue;
+            }
+            (Some(_), TrivialPolicy::Split) => true,
+            _                               => false,
+        };
+
+        let profile = match study {
+            true  => None,
+            false => Some(search_profile(&mut engine, &cand.board_post_blunder, &seq.moves, depths.solve).await?),
+        };
+        let human = match args.human_check && !study {
+            true  => Some(human_check(&mut engine, &cand.board_post_blunder, &seq.moves, depths.solve).await?),
+            false => None,
+        };
+        let puzzle = match study {
+            true  => draft,
+            false => match process_puzzle(&cand, &seq, &hdrs, profile.as_ref(), human.as_ref(), args.export) {
+                Ok(puzzle) => puzzle,
+                Err(_)     => continue,
+            },
+        };
+
+        // trivial separado: vai só para o conjunto fácil, contado uma vez
+        if easy {
+            if let Some(file) = easy_file.as_mut() {
+                if export_puzzle(&puzzle.pgn, file).is_ok() {
+                    puzzle.record(&mut stats);
+                    stats.add_found(1);
+                    easy_total += 1;
                 }
             }
+            continue;
+        }
+        if export_puzzle(&puzzle.pgn, &mut out_file).is_ok() {
+            if let Some(file) = refutation_file.as_mut() {
+                let record = serde_json::json!({ "fen": puzzle.fen, "refutations": puzzle.refutations });
+                export_json_line(&record, file)?;
+            }
+            puzzle.record(&mut stats);
+            stats.add_found(1);
+            total += 1;
         }
     }
 
@@ -69,5 +194,14 @@ pub async fn generate_puzzles(
     }
 
     info!("finalizado: {total} puzzles em {:.2?}", t0.elapsed());
+    if easy_file.is_some() { info!("conjunto fácil: {easy_total} puzzles"); }
+
+    // punidos × não punidos (MissedWin), lance único e estudos
+    let mut kinds: Vec<_> = stats.kind_stats.iter().collect();
+    kinds.sort();
+    if !kinds.is_empty() {
+        let split: Vec<String> = kinds.iter().map(|(kind, n)| format!("{kind}: {n}")).collect();
+        info!("por tipo: {}", split.join(", "));
+    }
     Ok(GenerateResult { puzzles: total })
 }
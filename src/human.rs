@@ -0,0 +1,76 @@
+// src/human.rs
+// ---------------------------------------------------------------------------
+// Checagem de "humanidade": repete a linha de solução com o motor limitado
+// (UCI_LimitStrength + UCI_Elo) em vários níveis e registra quem a encontra.
+// ---------------------------------------------------------------------------
+
+use anyhow::Result;
+use shakmaty::{Chess, Move, Position};
+
+use crate::{config, engine::Engine};
+
+/// Resultado por nível de força e faixa derivada.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HumanCheck {
+    pub steps: Vec<(u32, bool)>,     // (Elo, encontrou todos os lances do resolvedor)
+}
+
+impl HumanCheck {
+    /// Menor Elo a partir do qual todos os níveis acima resolvem.
+    pub fn solved_by(&self) -> Option<u32> {
+        let first_fail = self.steps.iter().rposition(|(_, ok)| !ok);
+        let from = first_fail.map_or(0, |i| i + 1);
+        self.steps.get(from).map(|(elo, _)| *elo)
+    }
+
+    /// Maior Elo que falhou abaixo de `solved_by` (ou o mais forte, se nenhum resolveu).
+    pub fn failed_by(&self) -> Option<u32> {
+        self.steps.iter().rev().find(|(_, ok)| !ok).map(|(elo, _)| *elo)
+    }
+
+    /// Faixa legível: "1500" (resolvido a partir de), "≤1350" ou ">2400".
+    pub fn band(&self) -> String {
+        match (self.solved_by(), self.failed_by()) {
+            (Some(s), None)    => format!("≤{s}"),
+            (Some(s), Some(_)) => s.to_string(),
+            (None, Some(f))    => format!(">{f}"),
+            (None, None)       => "?".into(),
+        }
+    }
+
+    /// "1350:no 1500:yes …" para o header `HumanCheck`.
+    pub fn summary(&self) -> String {
+        self.steps
+            .iter()
+            .map(|(elo, ok)| format!("{elo}:{}", if *ok { "yes" } else { "no" }))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Joga a linha com o motor enfraquecido em cada nível de `HUMAN_ELO_STEPS`;
+/// o motor volta à força total ao final.
+pub async fn human_check(engine: &mut Engine, start: &Chess, moves: &[Move], depth: u8) -> Result<HumanCheck> {
+    let mut check = HumanCheck::default();
+    for elo in config::HUMAN_ELO_STEPS {
+        engine.limit_strength(Some(elo)).await?;
+        let solved = finds_line(engine, start, moves, depth).await?;
+        check.steps.push((elo, solved));
+    }
+    engine.limit_strength(None).await?;
+    Ok(check)
+}
+
+/// Verdadeiro se o `bestmove` do motor enfraquecido é cada lance do
+/// resolvedor (índices pares); a PV das infos é sempre a de força total.
+async fn finds_line(engine: &mut Engine, start: &Chess, moves: &[Move], depth: u8) -> Result<bool> {
+    let mut board = start.clone();
+    for (i, mv) in moves.iter().enumerate() {
+        if i % 2 == 0 {
+            let Some(bm) = engine.best_move(&board, depth).await? else { return Ok(false) };
+            if bm.r#move.to_move(&board)? != *mv { return Ok(false); }
+        }
+        board.play_unchecked(mv);
+    }
+    Ok(true)
+}
@@ -6,26 +6,41 @@
 use std::{fs, path::PathBuf, process::Command};
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use log::{info, error};
 
 mod analysis;
+mod book;
 mod builder;
 mod candidates;
 mod config;
+mod difficulty;
+mod draws;
+mod eco;
+mod endgame;
 mod engine;
 mod exporter;
 mod generator;
+mod human;
+mod mates;
+mod material;
+mod motifs;
+mod player;
+mod report;
 mod resume;
+mod see;
 mod statistics;
 mod utils;
 mod visual;
 
 /// Args CLI - Argumentos da linha de comando para configuração
 #[derive(Parser, Debug)]
-#[command(author, version, about)]
+#[command(author, version, about, args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Args {
-    pub input: PathBuf,                                       // Arquivo PGN de entrada
+    #[command(subcommand)]
+    pub command: Option<Commands>,                            // Subcomandos (ex.: report)
+    #[arg(required = true)]
+    pub input: Option<PathBuf>,                               // Arquivo PGN de entrada
     #[arg(short, long)]
     pub output: Option<PathBuf>,                              // Saída opcional (ou usa padrão)
     #[arg(short, long, default_value_t = config::DEFAULT_DEPTH)]
@@ -34,10 +49,46 @@ pub struct Args {
     pub resume: bool,                                         // Flag para retomar processamento
     #[arg(short, long)]
     pub verbose: bool,                                        // Verbosidade
+    #[arg(long, value_enum, default_value_t = candidates::ExtractionMode::Blunders)]
+    pub mode: candidates::ExtractionMode,                     // Blunders, defesas de lance único ou ambos
+    #[arg(long)]
+    pub book: Option<PathBuf>,                                // Livro Polyglot (.bin): pula lances de teoria
+    #[arg(long = "player")]
+    pub players: Vec<String>,                                 // Nome do jogador (repetir para aliases)
+    #[arg(long, value_enum, default_value_t = player::PlayerFocus::All)]
+    pub player_focus: player::PlayerFocus,                    // Erros do jogador, oportunidades ou ambos
+    #[arg(long)]
+    pub sacrifices_only: bool,                                // Mantém apenas puzzles com sacrifício
+    #[arg(long = "theme")]
+    pub themes: Vec<String>,                                  // Mantém puzzles com todos estes temas (repetível)
+    #[arg(long, value_enum, default_value_t = see::TrivialPolicy::Keep)]
+    pub trivial: see::TrivialPolicy,                          // Peça pendurada/recaptura: manter, rejeitar ou separar
+    #[arg(long, default_value_t = config::DEFAULT_BRANCHING)]
+    pub branching: usize,                                     // Defesas alternativas do adversário por nó (0 = só a melhor)
+    #[arg(long, default_value_t = config::DEFAULT_MAX_NODES)]
+    pub max_nodes: usize,                                     // Limite de lances na árvore de solução
+    #[arg(long, value_enum, default_value_t = builder::DefensePolicy::Best)]
+    pub defense: builder::DefensePolicy,                      // Defesa do adversário na linha principal
+    #[arg(long, value_enum, default_value_t = builder::MistakePolicy::Forcing)]
+    pub mistakes: builder::MistakePolicy,                     // Erros plausíveis refutados: ordem do motor ou lances forçantes
+    /// 2ª passada com motor enfraquecido (UCI_Elo 1350–2400; o piso é 1350, exibido como "≤1350")
+    #[arg(long)]
+    pub human_check: bool,                                    // 2ª passada com motor enfraquecido (faixas de Elo)
+    #[arg(long)]
+    pub annotate: bool,                                       // [%eval], ??/! e refutações dos erros plausíveis
+    #[arg(long, value_enum, default_value_t = exporter::RefutationExport::None)]
+    pub refutations: exporter::RefutationExport,              // Linhas dos erros tentadores: variantes ou JSON
     #[arg(long, default_value = "info")]
     pub log_level: String,                                    // Nível de logging
 }
 
+/// Subcomandos disponíveis
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Relatório de fraquezas a partir dos puzzles extraídos de um jogador
+    Report(report::ReportArgs),
+}
+
 /// Configura o logger com o nível especificado
 fn setup_logger(level:&str){ env_logger::Builder::new().filter_level(level.parse().unwrap_or(log::LevelFilter::Info)).init(); }
 
@@ -57,14 +108,38 @@ async fn main() -> Result<()> {
     let args = Args::parse();
     setup_logger(&args.log_level);
 
+    // Subcomando de relatório não precisa do motor
+    if let Some(Commands::Report(rep)) = &args.command {
+        return report::run(rep);
+    }
+    let input = args.input.clone().context("arquivo PGN de entrada ausente")?;
+
     // Verifica disponibilidade do Stockfish
     ensure_stockfish()?;
 
     // Prepara argumentos para o gerador
-    let gen_args = generator::GeneratorArgs { base_depth: args.depth, resume: args.resume, verbose: args.verbose };
+    let gen_args = generator::GeneratorArgs {
+        base_depth: args.depth,
+        resume:     args.resume,
+        verbose:    args.verbose,
+        player:     player::PlayerFilter::new(&args.players, args.player_focus),
+        mode:       args.mode,
+        book:       args.book.clone(),
+        sacrifices_only: args.sacrifices_only,
+        trivial:    args.trivial,
+        themes:     args.themes.clone(),
+        human_check: args.human_check,
+        export:     exporter::ExportOptions { annotate: args.annotate, refutations: args.refutations },
+        tree:       builder::TreeOptions {
+            branching: args.branching,
+            max_nodes: args.max_nodes,
+            defense:   args.defense,
+            mistakes:  args.mistakes,
+        },
+    };
 
     // Executa o gerador de puzzles
-    let result = generator::generate_puzzles(&args.input, args.output.as_ref(), gen_args)
+    let result = generator::generate_puzzles(&input, args.output.as_ref(), gen_args)
         .await
         .context("erro gerando puzzles")?;
 
@@ -0,0 +1,99 @@
+// src/material.rs
+// ---------------------------------------------------------------------------
+// Balanço material ao longo da solução e detecção de sacrifícios do resolvedor.
+// ---------------------------------------------------------------------------
+
+use shakmaty::{Board, Chess, Color, Move, Position, Role};
+
+use crate::{config, motifs::piece_value};
+
+/// Material (cp) de `color` menos o do adversário; o rei não entra na conta.
+pub fn material_balance(board: &Board, color: Color) -> i32 {
+    let side = |c: Color| -> i32 {
+        [Role::Pawn, Role::Knight, Role::Bishop, Role::Rook, Role::Queen]
+            .iter()
+            .map(|&r| piece_value(r) * board.by_piece(r.of(c)).count() as i32)
+            .sum()
+    };
+    side(color) - side(!color)
+}
+
+/// Balanço do ponto de vista do resolvedor antes da linha e após cada ply.
+pub fn material_track(start: &Chess, moves: &[Move], solver: Color) -> Vec<i32> {
+    let mut board = start.clone();
+    let mut track = vec![material_balance(board.board(), solver)];
+    for mv in moves {
+        board.play_unchecked(mv);
+        track.push(material_balance(board.board(), solver));
+    }
+    track
+}
+
+/// Sacrifício: material investido pelo resolvedor e ganho líquido no fim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sacrifice {
+    pub invested: i32,
+    pub net_gain: i32,
+}
+
+/// Detecta sacrifício: após alguma resposta do adversário o resolvedor fica
+/// com ao menos `SACRIFICE_MIN_CP` a menos que no início, e termina a linha
+/// recuperando mais do que investiu — ou dando mate.
+pub fn detect_sacrifice(track: &[i32], mate: bool) -> Option<Sacrifice> {
+    let (&first, &last) = (track.first()?, track.last()?);
+    // índices pares (≥ 2) do track = posições após respostas do adversário
+    let low = track.iter().skip(2).step_by(2).copied().min()?;
+    let invested = first - low;
+    let net_gain = last - first;
+    (invested >= config::SACRIFICE_MIN_CP && (net_gain > 0 || mate))
+        .then_some(Sacrifice { invested, net_gain })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shakmaty::{fen::Fen, uci::UciMove, CastlingMode};
+
+    #[test]
+    fn track_follows_each_ply() {
+        // Bxf7+ Kxf7 Ng5+: o bispo é entregue e não volta
+        let mut pos: Chess = "4k3/5p2/8/8/2B5/5N2/8/6K1 w - - 0 1"
+            .parse::<Fen>().unwrap().into_position(CastlingMode::Standard).unwrap();
+        let start = pos.clone();
+        let moves: Vec<Move> = ["c4f7", "e8f7", "f3g5"].iter().map(|u| {
+            let mv = u.parse::<UciMove>().unwrap().to_move(&pos).unwrap();
+            pos.play_unchecked(&mv);
+            mv
+        }).collect();
+        let track = material_track(&start, &moves, Color::White);
+        assert_eq!(track, vec![500, 600, 300, 300]);
+
+        // investiu 200 e não recuperou: só conta como sacrifício se terminar em mate
+        assert_eq!(detect_sacrifice(&track, false), None);
+        assert_eq!(detect_sacrifice(&track, true), Some(Sacrifice { invested: 200, net_gain: -200 }));
+    }
+
+    #[test]
+    fn invested_versus_net_gain() {
+        let cases: [(&[i32], bool, Option<Sacrifice>); 5] = [
+            // qualidade e peão entregues, dama recuperada no fim
+            (&[0, 0, -500, -500, 400],  false, Some(Sacrifice { invested: 500, net_gain: 400 })),
+            // o ganho líquido só precisa ser positivo
+            (&[0, 0, -300, -300, 100],  false, Some(Sacrifice { invested: 300, net_gain: 100 })),
+            // investimento abaixo de SACRIFICE_MIN_CP
+            (&[0, 100, -100, -100, 200], false, None),
+            // material não recuperado; com mate vira sacrifício
+            (&[0, 0, -300, -300, -300], false, None),
+            (&[0, 0, -300, -300, -300], true,  Some(Sacrifice { invested: 300, net_gain: -300 })),
+        ];
+        for (track, mate, expected) in cases {
+            assert_eq!(detect_sacrifice(track, mate), expected, "{track:?}");
+        }
+    }
+
+    #[test]
+    fn solver_side_dips_do_not_count() {
+        // a queda após o lance do resolvedor (troca em andamento) não é investimento
+        assert_eq!(detect_sacrifice(&[0, -500, 0, 300, 300], false), None);
+    }
+}
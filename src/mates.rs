@@ -0,0 +1,264 @@
+// src/mates.rs
+// ---------------------------------------------------------------------------
+// Padrões de mate nomeados: classifica a posição final de um puzzle de mate
+// (tabuleiro já em xeque‑mate, lado a mover = rei mateado) por geometria.
+// ---------------------------------------------------------------------------
+
+use shakmaty::{attacks, Bitboard, Board, Chess, Color, File, Position, Rank, Role, Square};
+
+/// Padrão de mate reconhecido na posição final.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatePattern {
+    Smothered,
+    Hook,
+    Arabian,
+    Anastasia,
+    Boden,
+    Lolli,
+    Dovetail,
+    Epaulette,
+    BackRank,
+    Corridor,
+}
+
+impl MatePattern {
+    pub const ALL: [MatePattern; 10] = [
+        MatePattern::Smothered, MatePattern::Hook, MatePattern::Arabian, MatePattern::Anastasia,
+        MatePattern::Boden, MatePattern::Lolli, MatePattern::Dovetail, MatePattern::Epaulette,
+        MatePattern::BackRank, MatePattern::Corridor,
+    ];
+
+    /// Nome do tema no padrão Lichess (header `Themes`).
+    pub fn tag(self) -> &'static str {
+        match self {
+            MatePattern::Smothered => "smotheredMate",
+            MatePattern::Hook      => "hookMate",
+            MatePattern::Arabian   => "arabianMate",
+            MatePattern::Anastasia => "anastasiaMate",
+            MatePattern::Boden     => "bodenMate",
+            MatePattern::Lolli     => "lolliMate",
+            MatePattern::Dovetail  => "dovetailMate",
+            MatePattern::Epaulette => "epauletteMate",
+            MatePattern::BackRank  => "backRankMate",
+            MatePattern::Corridor  => "corridorMate",
+        }
+    }
+}
+
+/// Tamanho do mate em lances do resolvedor: `mateIn1` … `mateIn5+`.
+pub fn mate_length_tag(solver_moves: usize) -> &'static str {
+    match solver_moves {
+        0 | 1 => "mateIn1",
+        2     => "mateIn2",
+        3     => "mateIn3",
+        4     => "mateIn4",
+        _     => "mateIn5+",
+    }
+}
+
+/// Classifica a posição final; `None` se não for mate ou não casar padrão.
+/// A ordem de teste vai do padrão mais específico ao mais genérico.
+pub fn classify_mate(pos: &Chess) -> Option<MatePattern> {
+    if !pos.is_checkmate() { return None; }
+    let m = MateGeometry::new(pos)?;
+
+    [
+        (MatePattern::Smothered, m.smothered()),
+        (MatePattern::Hook,      m.hook()),
+        (MatePattern::Arabian,   m.arabian()),
+        (MatePattern::Anastasia, m.anastasia()),
+        (MatePattern::Boden,     m.boden()),
+        (MatePattern::Lolli,     m.lolli()),
+        (MatePattern::Dovetail,  m.dovetail()),
+        (MatePattern::Epaulette, m.epaulette()),
+        (MatePattern::BackRank,  m.back_rank()),
+        (MatePattern::Corridor,  m.corridor()),
+    ]
+    .into_iter()
+    .find_map(|(p, hit)| hit.then_some(p))
+}
+
+/// Dados comuns a todos os padrões: rei mateado, peça que dá xeque e vizinhança.
+struct MateGeometry<'a> {
+    board:     &'a Board,
+    mated:     Color,
+    king:      Square,
+    checker:   Square,
+    role:      Role,
+    neighbors: Bitboard,
+    own:       Bitboard,
+}
+
+impl<'a> MateGeometry<'a> {
+    fn new(pos: &'a Chess) -> Option<Self> {
+        let board   = pos.board();
+        let mated   = pos.turn();
+        let king    = board.king_of(mated)?;
+        let checker = pos.checkers().first()?;
+        Some(Self {
+            board,
+            mated,
+            king,
+            checker,
+            role:      board.role_at(checker)?,
+            neighbors: attacks::king_attacks(king),
+            own:       board.by_color(mated),
+        })
+    }
+
+    /// Peças do lado que aplica o mate com o papel dado.
+    fn attackers(&self, role: Role) -> Bitboard {
+        self.board.by_piece(role.of(!self.mated))
+    }
+
+    fn adjacent(&self, sq: Square) -> bool { self.neighbors.contains(sq) }
+
+    fn on_edge_file(&self) -> bool { matches!(self.king.file(), File::A | File::H) }
+
+    fn back_rank(&self) -> bool {
+        let (back, forward) = match self.mated {
+            Color::White => (Rank::First, Rank::Second),
+            Color::Black => (Rank::Eighth, Rank::Seventh),
+        };
+        let front = self.neighbors & Bitboard::from_rank(forward);
+        self.king.rank() == back
+            && matches!(self.role, Role::Rook | Role::Queen)
+            && self.checker.rank() == back
+            && (front & self.own).any()
+            && front.into_iter().all(|sq| self.own.contains(sq) || self.covered(sq))
+    }
+
+    /// Casa controlada pelo lado que aplica o mate (rei removido das linhas).
+    fn covered(&self, sq: Square) -> bool {
+        let occ = self.board.occupied().without(self.king);
+        self.board.attacks_to(sq, !self.mated, occ).any()
+    }
+
+    fn smothered(&self) -> bool {
+        self.role == Role::Knight && self.neighbors.is_subset(self.own)
+    }
+
+    /// Torre adjacente dando xeque, protegida por cavalo.
+    fn rook_guarded_by_knight(&self) -> Option<Square> {
+        if self.role != Role::Rook || !self.adjacent(self.checker) { return None; }
+        (attacks::knight_attacks(self.checker) & self.attackers(Role::Knight)).first()
+    }
+
+    fn hook(&self) -> bool {
+        self.rook_guarded_by_knight().is_some_and(|n| {
+            (self.board.attacks_to(n, !self.mated, self.board.occupied()) & self.attackers(Role::Pawn)).any()
+        })
+    }
+
+    fn arabian(&self) -> bool {
+        let corner = matches!(self.king.file(), File::A | File::H)
+            && matches!(self.king.rank(), Rank::First | Rank::Eighth);
+        corner && self.rook_guarded_by_knight().is_some()
+    }
+
+    fn anastasia(&self) -> bool {
+        let knight_cover = self.attackers(Role::Knight)
+            .into_iter()
+            .any(|n| (attacks::knight_attacks(n) & self.neighbors).any());
+        self.on_edge_file()
+            && matches!(self.role, Role::Rook | Role::Queen)
+            && self.checker.file() == self.king.file()
+            && knight_cover
+            && (self.neighbors & self.own).any()
+    }
+
+    fn boden(&self) -> bool {
+        let bishops = self.attackers(Role::Bishop);
+        self.role == Role::Bishop
+            && bishops.into_iter().any(|b| b.is_light() != self.checker.is_light())
+            && (self.neighbors & self.own).any()
+    }
+
+    /// Dama colada ao rei, na mesma coluna, apoiada por peão.
+    fn lolli(&self) -> bool {
+        self.role == Role::Queen
+            && self.adjacent(self.checker)
+            && self.checker.file() == self.king.file()
+            && (self.board.attacks_to(self.checker, !self.mated, self.board.occupied()) & self.attackers(Role::Pawn)).any()
+    }
+
+    /// Dama na diagonal adjacente; as casas que ela não controla estão
+    /// bloqueadas pelas próprias peças do rei.
+    fn dovetail(&self) -> bool {
+        let diagonal = self.adjacent(self.checker)
+            && self.checker.file() != self.king.file()
+            && self.checker.rank() != self.king.rank();
+        let occ      = self.board.occupied().without(self.king);
+        let queen    = attacks::queen_attacks(self.checker, occ);
+        let free     = self.neighbors.without(self.checker) & !queen;
+        self.role == Role::Queen && diagonal && free.any() && free.is_subset(self.own)
+    }
+
+    /// Rei entre duas peças próprias na mesma fileira, xeque de dama pela coluna.
+    fn epaulette(&self) -> bool {
+        let sides = self.neighbors & Bitboard::from_rank(self.king.rank());
+        self.role == Role::Queen
+            && self.checker.file() == self.king.file()
+            && self.checker.distance(self.king) >= 2
+            && sides.count() == 2
+            && sides.is_subset(self.own)
+    }
+
+    /// Corredor: xeque de longe por coluna/fileira, laterais fechadas.
+    fn corridor(&self) -> bool {
+        let along_file = self.checker.file() == self.king.file();
+        let along_rank = self.checker.rank() == self.king.rank();
+        let lateral = if along_file {
+            self.neighbors & Bitboard::from_rank(self.king.rank())
+        } else {
+            self.neighbors & Bitboard::from_file(self.king.file())
+        };
+        matches!(self.role, Role::Rook | Role::Queen)
+            && (along_file || along_rank)
+            && self.checker.distance(self.king) >= 2
+            && (lateral & self.own).any()
+            && lateral.into_iter().all(|sq| self.own.contains(sq) || self.covered(sq))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shakmaty::{fen::Fen, CastlingMode};
+
+    fn pattern(fen: &str) -> Option<MatePattern> {
+        let pos: Chess = fen.parse::<Fen>().unwrap().into_position(CastlingMode::Standard).unwrap();
+        classify_mate(&pos)
+    }
+
+    #[test]
+    fn known_mates() {
+        let cases = [
+            ("3R2k1/5ppp/8/8/8/8/8/6K1 b - - 0 1",          MatePattern::BackRank),
+            ("6rk/5Npp/8/8/8/8/8/6K1 b - - 0 1",            MatePattern::Smothered),
+            ("8/4N1pk/8/7R/8/8/8/6K1 b - - 0 1",            MatePattern::Anastasia),
+            ("7k/7R/5N2/8/8/8/8/6K1 b - - 0 1",             MatePattern::Arabian),
+            ("3nkn2/4R3/8/3N4/4P3/8/8/6K1 b - - 0 1",       MatePattern::Hook),
+            ("2kr4/3p4/B7/8/5B2/8/8/6K1 b - - 0 1",         MatePattern::Boden),
+            ("4kr2/3Q4/8/8/8/8/8/3R2K1 b - - 0 1",          MatePattern::Dovetail),
+            ("3rkr2/8/4Q3/8/8/8/8/6K1 b - - 0 1",           MatePattern::Epaulette),
+            ("6k1/5pQp/5P2/8/8/8/8/6K1 b - - 0 1",          MatePattern::Lolli),
+            ("8/8/8/6p1/6pk/6p1/8/4K2R b - - 0 1",          MatePattern::Corridor),
+        ];
+        for (fen, expected) in cases {
+            assert_eq!(pattern(fen), Some(expected), "{fen}");
+        }
+    }
+
+    #[test]
+    fn not_mate_is_unclassified() {
+        assert_eq!(pattern("6k1/5ppp/8/8/8/8/8/3R2K1 b - - 0 1"), None);
+    }
+
+    #[test]
+    fn mate_length_tags() {
+        assert_eq!(mate_length_tag(1), "mateIn1");
+        assert_eq!(mate_length_tag(3), "mateIn3");
+        assert_eq!(mate_length_tag(7), "mateIn5+");
+    }
+}
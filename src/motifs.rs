@@ -0,0 +1,415 @@
+// src/motifs.rs
+// ---------------------------------------------------------------------------
+// Motivos táticos por regras: percorre os lances do resolvedor em `PuzzleSeq`
+// e usa os conjuntos de ataque do shakmaty para rotular garfo, cravada,
+// espeto, ataque/xeque descoberto, xeque duplo, raio‑x e defensor sobrecarregado.
+// ---------------------------------------------------------------------------
+
+use shakmaty::{attacks, Bitboard, Board, Chess, Color, Move, Piece, Position, Role, Square};
+
+/// Motivo geométrico encontrado em algum lance do resolvedor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Motif {
+    Fork,
+    Pin,
+    Skewer,
+    DiscoveredAttack,
+    DiscoveredCheck,
+    DoubleCheck,
+    XRayAttack,
+    OverloadedDefender,
+    Intermezzo,
+    Deflection,
+    Attraction,
+}
+
+impl Motif {
+    pub const ALL: [Motif; 11] = [
+        Motif::Fork, Motif::Pin, Motif::Skewer, Motif::DiscoveredAttack, Motif::DiscoveredCheck,
+        Motif::DoubleCheck, Motif::XRayAttack, Motif::OverloadedDefender, Motif::Intermezzo,
+        Motif::Deflection, Motif::Attraction,
+    ];
+
+    /// Nome do tema no padrão Lichess (header `Themes`).
+    pub fn tag(self) -> &'static str {
+        match self {
+            Motif::Fork               => "fork",
+            Motif::Pin                => "pin",
+            Motif::Skewer             => "skewer",
+            Motif::DiscoveredAttack   => "discoveredAttack",
+            Motif::DiscoveredCheck    => "discoveredCheck",
+            Motif::DoubleCheck        => "doubleCheck",
+            Motif::XRayAttack         => "xRayAttack",
+            Motif::OverloadedDefender => "overloading",
+            Motif::Intermezzo         => "intermezzo",
+            Motif::Deflection         => "deflection",
+            Motif::Attraction         => "attraction",
+        }
+    }
+}
+
+/// Mecânica especial de um lance do resolvedor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoveTag {
+    Promotion,
+    UnderPromotion,
+    EnPassant,
+    Castling,
+    Check,
+    Capture,
+    QuietMove,
+}
+
+impl MoveTag {
+    /// Nome do tema no padrão Lichess (header `Themes`).
+    pub fn tag(self) -> &'static str {
+        match self {
+            MoveTag::Promotion      => "promotion",
+            MoveTag::UnderPromotion => "underPromotion",
+            MoveTag::EnPassant      => "enPassant",
+            MoveTag::Castling       => "castling",
+            MoveTag::Check          => "check",
+            MoveTag::Capture        => "capture",
+            MoveTag::QuietMove      => "quietMove",
+        }
+    }
+}
+
+/// Valor material (cp) de cada peça; o rei vale mais que qualquer troca.
+pub fn piece_value(role: Role) -> i32 {
+    match role {
+        Role::Pawn   => 100,
+        Role::Knight => 300,
+        Role::Bishop => 300,
+        Role::Rook   => 500,
+        Role::Queen  => 900,
+        Role::King   => 10_000,
+    }
+}
+
+/// Percorre a linha a partir de `start` (lances do resolvedor nos índices
+/// pares) e devolve os motivos encontrados, sem repetição e em ordem.
+pub fn detect_motifs(start: &Chess, moves: &[Move], solver: Color) -> Vec<Motif> {
+    let mut found = Vec::new();
+    let mut board = start.clone();
+
+    for mv in moves {
+        let before = board.clone();
+        board.play_unchecked(mv);
+        if before.turn() != solver { continue; }
+
+        for m in motifs_of_move(&before, mv, &board) {
+            if !found.contains(&m) { found.push(m); }
+        }
+    }
+    found
+}
+
+/// Classifica os lances do resolvedor (índices pares de `moves`). Um lance
+/// quieto — sem xeque nem captura — só conta se for o único que vence,
+/// conforme `unique` (um valor por lance do resolvedor).
+pub fn detect_move_tags(start: &Chess, moves: &[Move], unique: &[bool]) -> Vec<MoveTag> {
+    let mut found = Vec::new();
+    let mut board = start.clone();
+
+    for (i, mv) in moves.iter().enumerate() {
+        board.play_unchecked(mv);
+        if i % 2 != 0 { continue; }
+        let check = board.is_check();
+
+        let tags = [
+            (MoveTag::Promotion,      mv.promotion() == Some(Role::Queen)),
+            (MoveTag::UnderPromotion, mv.promotion().is_some_and(|r| r != Role::Queen)),
+            (MoveTag::EnPassant,      mv.is_en_passant()),
+            (MoveTag::Castling,       mv.is_castle()),
+            (MoveTag::Check,          check),
+            (MoveTag::Capture,        mv.is_capture()),
+            (MoveTag::QuietMove,      !check && !mv.is_capture() && !mv.is_promotion()
+                                      && unique.get(i / 2).copied().unwrap_or(false)),
+        ];
+        for (tag, hit) in tags {
+            if hit && !found.contains(&tag) { found.push(tag); }
+        }
+    }
+    found
+}
+
+/// Ideias de sequência: compara as casas atacadas/defendidas entre posições
+/// consecutivas da linha (resolvedor nos índices pares, respostas do motor nos
+/// ímpares). `prev` é o lance que antecede a linha (o blunder).
+pub fn detect_sequence_motifs(start: &Chess, prev: Option<&Move>, moves: &[Move]) -> Vec<Motif> {
+    let mut found     = Vec::new();
+    let mut positions = vec![start.clone()];
+    for mv in moves {
+        let mut next = positions[positions.len() - 1].clone();
+        next.play_unchecked(mv);
+        positions.push(next);
+    }
+
+    for i in (0..moves.len()).step_by(2) {
+        let last = if i == 0 { prev } else { moves.get(i - 1) };
+        let mut hits = Vec::new();
+        if last.is_some_and(|l| is_intermezzo(&positions[i], l, &moves[i], &positions[i + 1])) {
+            hits.push(Motif::Intermezzo);
+        }
+        if let (Some(reply), Some(next)) = (moves.get(i + 1), moves.get(i + 2)) {
+            if is_deflection(&positions[i + 1], reply, &positions[i + 2], next) { hits.push(Motif::Deflection); }
+            if is_attraction(&moves[i], reply, next, &positions[i + 3]) { hits.push(Motif::Attraction); }
+        }
+        for m in hits {
+            if !found.contains(&m) { found.push(m); }
+        }
+    }
+    found
+}
+
+/// Lance intermediário: o adversário acabou de capturar e a recaptura estava
+/// disponível, mas o resolvedor prefere um xeque ou uma nova ameaça.
+fn is_intermezzo(before: &Chess, last: &Move, mv: &Move, after: &Chess) -> bool {
+    if !last.is_capture() || mv.to() == last.to() { return false; }
+    let can_recapture = before.legal_moves().iter().any(|m| m.to() == last.to() && m.is_capture());
+    if !can_recapture { return false; }
+
+    let us     = before.turn();
+    let threat = after.board().role_at(mv.to()).is_some_and(|role| {
+        let fresh = after.board().attacks_from(mv.to()) & after.board().by_color(!us);
+        fresh.into_iter().any(|t| worth_attacking(after.board(), role, t, us))
+    });
+    after.is_check() || threat
+}
+
+/// Desvio: a resposta tira um defensor (que não o rei) de uma casa que ele
+/// guardava e o lance seguinte do resolvedor explora exatamente essa casa.
+fn is_deflection(before: &Chess, reply: &Move, after: &Chess, next: &Move) -> bool {
+    let (Some(from), key) = (reply.from(), next.to()) else { return false };
+    if reply.role() == Role::King || reply.to() == key { return false; }
+    let guarded_before = before.board().attacks_from(from).contains(key);
+    let guarded_after  = after.board().attacks_from(reply.to()).contains(key);
+    let forced         = reply.is_capture() || before.is_check();
+    guarded_before && !guarded_after && forced
+}
+
+/// Atração: o adversário captura a isca e a peça capturante vira alvo — rei
+/// em xeque no lance seguinte, ou peça garfada a partir dali.
+fn is_attraction(bait: &Move, reply: &Move, next: &Move, after_next: &Chess) -> bool {
+    if !reply.is_capture() || reply.to() != bait.to() { return false; }
+    let lured = reply.to();
+    if reply.role() == Role::King { return after_next.is_check(); }
+
+    let us = !after_next.turn();
+    let b  = after_next.board();
+    !next.is_castle()
+        && b.attacks_from(next.to()).contains(lured)
+        && is_fork(b, next.to(), us)
+}
+
+/// Motivos criados por um único lance do resolvedor.
+fn motifs_of_move(before: &Chess, mv: &Move, after: &Chess) -> Vec<Motif> {
+    let mut out  = Vec::new();
+    let us       = before.turn();
+    let checkers = after.checkers();
+
+    if checkers.count() >= 2 { out.push(Motif::DoubleCheck); }
+    if mv.is_castle() { return out; }
+
+    let to         = mv.to();
+    let discovered = checkers.without(to).any();
+    if discovered { out.push(Motif::DiscoveredCheck); }
+    if is_fork(after.board(), to, us) { out.push(Motif::Fork); }
+    match line_through(after.board(), to, us) {
+        Some(LineMotif::Pin)    => out.push(Motif::Pin),
+        Some(LineMotif::Skewer) => out.push(Motif::Skewer),
+        None                    => {}
+    }
+    if !discovered && mv.from().is_some_and(|from| is_discovered_attack(before.board(), after.board(), from, to, us)) {
+        out.push(Motif::DiscoveredAttack);
+    }
+    if is_xray(after.board(), to, us) { out.push(Motif::XRayAttack); }
+    if mv.is_capture() && is_overloaded(after.board(), to, us) { out.push(Motif::OverloadedDefender); }
+    out
+}
+
+/// Um alvo "vale" o ataque quando é o rei, vale mais que o atacante ou está
+/// sem defesa.
+fn worth_attacking(board: &Board, attacker: Role, target: Square, us: Color) -> bool {
+    let Some(role) = board.role_at(target) else { return false };
+    role == Role::King
+        || piece_value(role) > piece_value(attacker)
+        || board.attacks_to(target, !us, board.occupied()).is_empty()
+}
+
+/// Garfo: a peça que chegou em `sq` ataca dois ou mais alvos relevantes.
+fn is_fork(board: &Board, sq: Square, us: Color) -> bool {
+    let Some(role) = board.role_at(sq) else { return false };
+    // uma peça que pode ser capturada de graça não sustenta o garfo
+    if role != Role::King
+        && board.attacks_to(sq, !us, board.occupied()).any()
+        && board.attacks_to(sq, us, board.occupied()).is_empty()
+    {
+        return false;
+    }
+    let targets = board.attacks_from(sq) & board.by_color(!us);
+    targets.into_iter().filter(|&t| worth_attacking(board, role, t, us)).count() >= 2
+}
+
+enum LineMotif { Pin, Skewer }
+
+/// Cravada/espeto: a peça de longo alcance em `sq` ataca uma peça inimiga
+/// com outra inimiga logo atrás, na mesma linha.
+fn line_through(board: &Board, sq: Square, us: Color) -> Option<LineMotif> {
+    let role = board.role_at(sq)?;
+    if !matches!(role, Role::Bishop | Role::Rook | Role::Queen) { return None; }
+    let piece = role.of(us);
+
+    for front in board.attacks_from(sq) & board.by_color(!us) {
+        let Some(behind) = behind_on_line(board, sq, front, piece) else { continue };
+        if board.color_at(behind) != Some(!us) { continue; }
+        let (vf, vb) = (piece_value(board.role_at(front)?), piece_value(board.role_at(behind)?));
+        if vf < vb { return Some(LineMotif::Pin); }
+        if vf > vb && worth_attacking(board, role, behind, us) { return Some(LineMotif::Skewer); }
+    }
+    None
+}
+
+/// Primeira peça atrás de `front` na linha `sq`→`front`, se houver.
+fn behind_on_line(board: &Board, sq: Square, front: Square, piece: Piece) -> Option<Square> {
+    let occ     = board.occupied();
+    let through = attacks::attacks(sq, piece, occ.without(front)) & !attacks::attacks(sq, piece, occ);
+    let beyond  = through & attacks::ray(sq, front) & occ;
+    beyond.into_iter().find(|&b| attacks::between(sq, b).contains(front))
+}
+
+/// Ataque descoberto: o lance libera a linha de outra peça nossa, que passa a
+/// atacar um alvo relevante.
+fn is_discovered_attack(before: &Board, after: &Board, from: Square, to: Square, us: Color) -> bool {
+    (after.sliders() & after.by_color(us)).without(to).into_iter().any(|s| {
+        let Some(role) = after.role_at(s) else { return false };
+        let fresh = after.attacks_from(s) & !before.attacks_from(s) & after.by_color(!us);
+        fresh.into_iter().any(|t| {
+            attacks::between(s, t).contains(from) && worth_attacking(after, role, t, us)
+        })
+    })
+}
+
+/// Raio‑x: outra peça nossa defende `sq` através da peça inimiga que pode
+/// recapturar ali — saindo a recaptura, a linha se abre.
+fn is_xray(board: &Board, sq: Square, us: Color) -> bool {
+    let occ = board.occupied();
+    (board.sliders() & board.by_color(us)).without(sq).into_iter().any(|s| {
+        let Some(piece) = board.piece_at(s) else { return false };
+        let blockers = attacks::between(s, sq) & occ;
+        let Some(blocker) = blockers.single_square() else { return false };
+        board.color_at(blocker) == Some(!us)
+            && board.attacks_from(blocker).contains(sq)
+            && attacks::attacks(s, piece, occ.without(blocker)).contains(sq)
+    })
+}
+
+/// Defensor sobrecarregado: após a captura em `sq`, o único defensor inimigo
+/// da casa também é o único defensor de outra peça que atacamos.
+fn is_overloaded(board: &Board, sq: Square, us: Color) -> bool {
+    let occ = board.occupied();
+    let Some(defender) = board.attacks_to(sq, !us, occ).single_square() else { return false };
+
+    let ours     = board.by_color(us);
+    let attacked = ours.into_iter().fold(Bitboard::EMPTY, |acc, s| acc | board.attacks_from(s));
+    let targets  = attacked & board.by_color(!us) & !board.kings();
+    targets.without(defender).into_iter().any(|t| {
+        board.attacks_to(t, !us, occ).single_square() == Some(defender)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shakmaty::{fen::Fen, uci::UciMove, CastlingMode};
+
+    fn position(fen: &str) -> Chess {
+        fen.parse::<Fen>().unwrap().into_position(CastlingMode::Standard).unwrap()
+    }
+
+    /// Lances UCI jogados em sequência a partir de `start`.
+    fn line(start: &Chess, ucis: &[&str]) -> Vec<Move> {
+        let mut pos = start.clone();
+        ucis.iter().map(|u| {
+            let mv = u.parse::<UciMove>().unwrap().to_move(&pos).unwrap();
+            pos.play_unchecked(&mv);
+            mv
+        }).collect()
+    }
+
+    fn motifs(fen: &str, ucis: &[&str]) -> Vec<Motif> {
+        let pos = position(fen);
+        detect_motifs(&pos, &line(&pos, ucis), pos.turn())
+    }
+
+    #[test]
+    fn known_motifs() {
+        let cases = [
+            // Nc7+ garfa rei e torre
+            ("r3k3/8/8/1N6/8/8/8/4K3 w - - 0 1",               "b5c7", Motif::Fork),
+            // Bb5 crava o cavalo no rei
+            ("4k3/8/2n5/8/8/8/8/4KB2 w - - 0 1",               "f1b5", Motif::Pin),
+            // Rd1+ espeta rei e dama
+            ("3q4/8/8/3k4/8/8/8/K6R w - - 0 1",                "h1d1", Motif::Skewer),
+            // o cavalo sai e a torre ataca a dama
+            ("4k3/4q3/8/8/4N3/8/8/K3R3 w - - 0 1",             "e4c3", Motif::DiscoveredAttack),
+            // o cavalo sai e a torre dá xeque
+            ("4k3/8/8/8/4N3/8/8/K3R3 w - - 0 1",               "e4c5", Motif::DiscoveredCheck),
+            // Nd6+ com xeque da torre ao mesmo tempo
+            ("4k3/8/8/8/4N3/8/8/K3R3 w - - 0 1",               "e4d6", Motif::DoubleCheck),
+            // a torre de d1 defende d6 através da torre preta
+            ("4k3/8/8/1N6/3r4/8/8/3RK3 w - - 0 1",             "b5d6", Motif::XRayAttack),
+            // Rxd4: a torre de d8 defende d4 e o bispo de b8
+            ("1b1r2k1/5ppp/8/8/3n4/8/8/1R1R2K1 w - - 0 1",     "d1d4", Motif::OverloadedDefender),
+        ];
+        for (fen, mv, expected) in cases {
+            assert!(motifs(fen, &[mv]).contains(&expected), "{fen}: {:?}", motifs(fen, &[mv]));
+        }
+    }
+
+    #[test]
+    fn quiet_move_has_no_motif() {
+        assert_eq!(motifs("4k3/8/8/8/8/8/8/4K3 w - - 0 1", &["e1d1"]), Vec::new());
+    }
+
+    /// Motivos de sequência da linha `ucis`, opcionalmente após o lance `prev`
+    /// do adversário (o blunder).
+    fn sequence(fen: &str, prev: Option<&str>, ucis: &[&str]) -> Vec<Motif> {
+        let mut pos = position(fen);
+        let prev = prev.map(|u| line(&pos, &[u]).remove(0));
+        if let Some(p) = &prev { pos.play_unchecked(p); }
+        detect_sequence_motifs(&pos, prev.as_ref(), &line(&pos, ucis))
+    }
+
+    #[test]
+    fn zwischenzug_before_recapture() {
+        // ...Bxc3 e, em vez de bxc3, Qa4+ primeiro
+        let found = sequence("4k3/8/8/8/1b6/2N5/1P6/3Q2K1 b - - 0 1", Some("b4c3"), &["d1a4"]);
+        assert!(found.contains(&Motif::Intermezzo), "{found:?}");
+        // recapturar direto não é lance intermediário
+        let found = sequence("4k3/8/8/8/1b6/2N5/1P6/3Q2K1 b - - 0 1", Some("b4c3"), &["b2c3"]);
+        assert!(!found.contains(&Motif::Intermezzo), "{found:?}");
+    }
+
+    #[test]
+    fn deflection_of_back_rank_guard() {
+        // Qd5 tira a torre da oitava fila e Re8 explora a casa que ela guardava
+        let found = sequence("3r2k1/5ppp/8/8/8/1Q6/8/4R1K1 w - - 0 1", None, &["b3d5", "d8d5", "e1e8"]);
+        assert!(found.contains(&Motif::Deflection), "{found:?}");
+        assert!(!found.contains(&Motif::Attraction), "{found:?}");
+    }
+
+    #[test]
+    fn attraction_of_the_king() {
+        // Bxf7+ Kxf7 Ng5+: o rei é atraído para o xeque
+        let found = sequence("4k3/5p2/8/8/2B5/5N2/8/6K1 w - - 0 1", None, &["c4f7", "e8f7", "f3g5"]);
+        assert!(found.contains(&Motif::Attraction), "{found:?}");
+    }
+
+    #[test]
+    fn quiet_sequence_has_no_idea() {
+        let found = sequence("4k3/8/8/8/8/8/8/4K3 w - - 0 1", None, &["e1d1", "e8d8", "d1c1"]);
+        assert_eq!(found, Vec::new());
+    }
+}
@@ -0,0 +1,74 @@
+// src/player.rs
+// ---------------------------------------------------------------------------
+// Modo focado em jogador: filtra partidas e candidatos pelo nome do jogador
+// (com aliases) casado contra os headers White/Black.
+// ---------------------------------------------------------------------------
+
+use clap::ValueEnum;
+use shakmaty::Color;
+
+use crate::candidates::PuzzleCandidate;
+
+/// Quais candidatos manter em relação ao jogador escolhido.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PlayerFocus {
+    /// O jogador cometeu o blunder ("aprenda com seus erros")
+    Mistakes,
+    /// O adversário errou e o jogador precisava encontrar o ganho
+    Opportunities,
+    /// Ambos os casos
+    All,
+}
+
+/// Filtro de jogador: nomes normalizados + foco desejado.
+#[derive(Debug, Clone)]
+pub struct PlayerFilter {
+    names: Vec<String>,
+    focus: PlayerFocus,
+}
+
+impl PlayerFilter {
+    /// Cria o filtro; `None` quando nenhum nome foi informado.
+    pub fn new(names: &[String], focus: PlayerFocus) -> Option<Self> {
+        let names: Vec<String> = names.iter().map(|n| normalize(n)).filter(|n| !n.is_empty()).collect();
+        (!names.is_empty()).then_some(Self { names, focus })
+    }
+
+    /// Cor do jogador na partida, conforme os headers White/Black.
+    pub fn color_in(&self, headers: &[(String, String)]) -> Option<Color> {
+        let matches = |key: &str| headers
+            .iter()
+            .any(|(k, v)| k.eq_ignore_ascii_case(key) && self.names.contains(&normalize(v)));
+        if matches("White") { Some(Color::White) }
+        else if matches("Black") { Some(Color::Black) }
+        else { None }
+    }
+
+    /// Indica se o jogador participa da partida (evita varrer jogos alheios).
+    pub fn plays_in(&self, headers: &[(String, String)]) -> bool {
+        self.color_in(headers).is_some()
+    }
+
+    /// Mantém o candidato conforme o foco: o blunderer é sempre `!solver_color`.
+    pub fn accepts(&self, cand: &PuzzleCandidate, headers: &[(String, String)]) -> bool {
+        let Some(color) = self.color_in(headers) else { return false };
+        match self.focus {
+            PlayerFocus::Mistakes      => color != cand.solver_color,
+            PlayerFocus::Opportunities => color == cand.solver_color,
+            PlayerFocus::All           => true,
+        }
+    }
+}
+
+/// Normaliza nomes: minúsculas, sem pontuação e com tokens ordenados,
+/// de modo que "Carlsen, Magnus" e "Magnus Carlsen" coincidam.
+fn normalize(name: &str) -> String {
+    let cleaned: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { ' ' })
+        .collect();
+    let mut tokens: Vec<&str> = cleaned.split_whitespace().collect();
+    tokens.sort_unstable();
+    tokens.join(" ")
+}
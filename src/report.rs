@@ -0,0 +1,183 @@
+// src/report.rs
+// ---------------------------------------------------------------------------
+// Relatório de fraquezas: relê os puzzles extraídos das partidas de um
+// jogador e agrega por objetivo tático, fase, abertura, apuro de tempo e
+// papel do jogador (cometeu o blunder ou deixou de punir).
+// ---------------------------------------------------------------------------
+
+use std::{collections::HashMap, fs::File, path::PathBuf};
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use serde::Serialize;
+use shakmaty::{fen::Fen, Color};
+
+use crate::{
+    config,
+    mates::MatePattern,
+    motifs::Motif,
+    player::{PlayerFilter, PlayerFocus},
+    statistics::PuzzleStatistics,
+    utils::read_pgn_headers,
+    visual::render_weakness_report,
+};
+
+/// Argumentos do subcomando `report`
+#[derive(clap::Args, Debug)]
+pub struct ReportArgs {
+    pub puzzles: PathBuf,                                     // PGN de puzzles gerado pelo extrator
+    #[arg(long = "player", required = true)]
+    pub players: Vec<String>,                                 // Nome do jogador (repetir para aliases)
+    #[arg(long)]
+    pub json: Option<PathBuf>,                                // Saída JSON (padrão: <puzzles>_report.json)
+}
+
+/// Papel do jogador no puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum PlayerRole {
+    Blunder,            // o próprio jogador errou
+    MissedPunishment,   // o adversário errou e o jogador não encontrou o ganho
+    Punished,           // o adversário errou e o jogador encontrou o ganho
+    Opportunity,        // o adversário errou; resposta real desconhecida
+    OnlyMove,           // lance único (defesa ou estudo de final) que o jogador enfrentou
+}
+
+impl PlayerRole {
+    /// Papéis que representam fraquezas do jogador
+    pub fn is_weakness(self) -> bool { matches!(self, Self::Blunder | Self::MissedPunishment) }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Weakness {
+    pub role:      PlayerRole,
+    pub motif:     String,      // motivo ou padrão de mate do header Themes ou, na falta, o objetivo tático
+    pub phase:     String,
+    pub count:     u64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct WeaknessReport {
+    pub player:              String,
+    pub stats:               PuzzleStatistics,
+    pub role_stats:          HashMap<String, u64>,
+    pub opening_stats:       HashMap<String, u64>,
+    pub time_pressure_stats: HashMap<String, u64>,
+    pub weaknesses:          Vec<Weakness>,
+}
+
+impl WeaknessReport {
+    /// Agrega os headers de todos os puzzles em que o jogador participa.
+    pub fn build(player_name: &str, player: &PlayerFilter, puzzles: &[IndexMap<String, String>]) -> Self {
+        let mut rep = Self { player: player_name.into(), ..Self::default() };
+        let mut combos: HashMap<(PlayerRole, String, String), u64> = HashMap::new();
+
+        for hdr in puzzles {
+            let Some(role) = role_of(player, hdr) else { continue };
+            let get = |k: &str| hdr.get(k).cloned().unwrap_or_else(|| "?".into());
+            let (objective, phase) = (get("Tactical"), get("Phase"));
+            let themes: Vec<&str> = hdr.get("Themes").map_or_else(Vec::new, |t| t.split_whitespace().collect());
+
+            rep.stats.add_found(1);
+            rep.stats.update_objective(&objective, 1);
+            rep.stats.update_phase(&phase, 1);
+            for theme in &themes { rep.stats.update_theme(theme, 1); }
+            *rep.role_stats.entry(format!("{:?}", role)).or_insert(0) += 1;
+            *rep.opening_stats.entry(opening_of(hdr)).or_insert(0) += 1;
+            // o relógio do header é o de quem errou: só vale quando foi o jogador
+            if role == PlayerRole::Blunder {
+                *rep.time_pressure_stats.entry(hdr.get("TimePressure").cloned().unwrap_or_else(|| "Unknown".into())).or_insert(0) += 1;
+            }
+            if role.is_weakness() {
+                let tactics: Vec<&str> = themes.iter().copied().filter(|t| is_tactic(t)).collect();
+                let motifs = if tactics.is_empty() { vec![objective.as_str()] } else { tactics };
+                for motif in motifs {
+                    *combos.entry((role, motif.to_string(), phase.clone())).or_insert(0) += 1;
+                }
+            }
+        }
+
+        rep.weaknesses = combos
+            .into_iter()
+            .map(|((role, motif, phase), count)| Weakness { role, motif, phase, count })
+            .collect();
+        rep.weaknesses.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.motif.cmp(&b.motif)));
+        rep
+    }
+
+    /// Resumo legível: as fraquezas mais frequentes por papel do jogador.
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for role in [PlayerRole::Blunder, PlayerRole::MissedPunishment] {
+            let verb = if role == PlayerRole::Blunder { "erra" } else { "deixa passar" };
+            lines.extend(
+                self.weaknesses
+                    .iter()
+                    .filter(|w| w.role == role)
+                    .take(config::REPORT_TOP_WEAKNESSES)
+                    .map(|w| format!("você {verb} táticas de {} no {} ({} puzzles)", w.motif, w.phase, w.count)),
+            );
+        }
+        if let Some(yes) = self.time_pressure_stats.get("Yes") {
+            let blunders: u64 = self.time_pressure_stats.values().sum();
+            lines.push(format!("{yes} de {blunders} erros seus surgiram em apuro de tempo"));
+        }
+        lines
+    }
+}
+
+/// Determina o papel do jogador: o lado a mover na FEN é quem cometeu o blunder.
+/// Em `OnlyMove`/`Study` o primeiro lance é um lance normal da partida; só
+/// conta o lado que resolve, e nunca como fraqueza.
+fn role_of(player: &PlayerFilter, hdr: &IndexMap<String, String>) -> Option<PlayerRole> {
+    let pairs: Vec<(String, String)> = hdr.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    let color = player.color_in(&pairs)?;
+    let blunderer: Color = hdr.get("FEN")?.parse::<Fen>().ok()?.as_setup().turn;
+
+    if matches!(hdr.get("Kind").map(String::as_str), Some("OnlyMove" | "Study")) {
+        return (color != blunderer).then_some(PlayerRole::OnlyMove);
+    }
+
+    Some(if color == blunderer {
+        PlayerRole::Blunder
+    } else {
+        match hdr.get("Kind").map(String::as_str) {
+            Some("MissedWin") => PlayerRole::MissedPunishment,
+            Some("Punished")  => PlayerRole::Punished,
+            _                 => PlayerRole::Opportunity,
+        }
+    })
+}
+
+/// Tema que descreve uma tática: motivo geométrico ou padrão de mate. Tags
+/// de mecânica ("check", "capture"), tamanho do mate e família de final ficam
+/// de fora das fraquezas.
+fn is_tactic(tag: &str) -> bool {
+    Motif::ALL.iter().any(|m| m.tag() == tag) || MatePattern::ALL.iter().any(|p| p.tag() == tag)
+}
+
+/// Chave de abertura: ECO + nome, quando disponíveis.
+fn opening_of(hdr: &IndexMap<String, String>) -> String {
+    match (hdr.get("ECO"), hdr.get("Opening")) {
+        (Some(eco), Some(name)) => format!("{eco} {name}"),
+        (Some(eco), None)       => eco.clone(),
+        (None, Some(name))      => name.clone(),
+        (None, None)            => "?".into(),
+    }
+}
+
+/// Executa o subcomando: lê os puzzles, grava o JSON e imprime o resumo.
+pub fn run(args: &ReportArgs) -> Result<()> {
+    let player = PlayerFilter::new(&args.players, PlayerFocus::All)
+        .context("informe ao menos um --player")?;
+    let puzzles = read_pgn_headers(&args.puzzles)?;
+    let report  = WeaknessReport::build(&args.players.join(" / "), &player, &puzzles);
+
+    let json_path = args.json.clone().unwrap_or_else(|| {
+        let stem = args.puzzles.file_stem().and_then(|s| s.to_str()).unwrap_or("puzzles");
+        args.puzzles.with_file_name(format!("{stem}_report.json"))
+    });
+    let file = File::create(&json_path).with_context(|| format!("criar {}", json_path.display()))?;
+    serde_json::to_writer_pretty(file, &report).context("Falha ao gravar relatório")?;
+
+    render_weakness_report(&report, &json_path)
+}
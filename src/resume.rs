@@ -55,6 +55,9 @@ pub fn initialize_resume(
                 "puzzles_rejected": 0,
                 "objective_stats": {},
                 "phase_stats": {},
+                "kind_stats": {},
+                "theme_stats": {},
+                "opening_stats": {},
                 "rejection_reasons": {}
             }
         });
@@ -0,0 +1,157 @@
+// src/see.rs
+// ---------------------------------------------------------------------------
+// Static Exchange Evaluation sobre os ataques do shakmaty e detecção de
+// puzzles triviais: o primeiro lance do resolvedor só captura uma peça
+// pendurada ou recaptura o que o blunder acabou de tomar.
+// ---------------------------------------------------------------------------
+
+use clap::ValueEnum;
+use shakmaty::{Board, Chess, Color, Move, Position, Role, Square};
+
+use crate::{config, motifs::piece_value};
+
+/// O que fazer com puzzles triviais.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TrivialPolicy {
+    /// Apenas marca o tema
+    Keep,
+    /// Descarta (motivo registrado nas estatísticas)
+    Reject,
+    /// Exporta num arquivo separado de puzzles fáceis
+    Split,
+}
+
+/// Por que o primeiro lance do resolvedor é trivial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrivialKind { HangingPiece, Recapture }
+
+impl TrivialKind {
+    /// Nome do tema (header `Themes`).
+    pub fn tag(self) -> &'static str {
+        match self {
+            TrivialKind::HangingPiece => "hangingPiece",
+            TrivialKind::Recapture    => "recapture",
+        }
+    }
+}
+
+/// Ganho material (cp) do lado que joga `mv` após toda a sequência de trocas
+/// em `mv.to()`, cada lado escolhendo a peça de menor valor e podendo parar.
+pub fn see(board: &Board, mv: &Move) -> i32 {
+    let Some(from) = mv.from() else { return 0 };
+    let to       = mv.to();
+    let us       = board.color_at(from).unwrap_or(Color::White);
+    let mut occ  = board.occupied().without(from);
+    let mut role = mv.promotion().unwrap_or(mv.role());
+    let mut side = !us;
+
+    let mut gain = vec![mv.capture().map_or(0, piece_value)];
+    if mv.is_en_passant() {
+        occ.discard(Square::from_coords(to.file(), from.rank()));
+    }
+
+    loop {
+        let d = gain.len();
+        gain.push(piece_value(role) - gain[d - 1]);
+
+        let attackers = board.attacks_to(to, side, occ) & occ;
+        let Some((sq, r)) = [Role::Pawn, Role::Knight, Role::Bishop, Role::Rook, Role::Queen, Role::King]
+            .into_iter()
+            .find_map(|r| (board.by_role(r) & attackers).first().map(|sq| (sq, r)))
+        else { break };
+
+        occ.discard(sq);
+        role = r;
+        side = !side;
+    }
+
+    // minimax de trás para frente: o último ganho especulativo é descartado
+    gain.pop();
+    while gain.len() > 1 {
+        let last = gain.pop().unwrap_or(0);
+        let prev = gain.last_mut().unwrap();
+        *prev = -(-*prev).max(last);
+    }
+    gain[0]
+}
+
+/// Classifica o primeiro lance do resolvedor como trivial quando só ganha a
+/// peça pendurada (SEE = valor capturado) ou recaptura na casa do blunder, e o
+/// resto da linha não acrescenta ao menos `HANGING_THRESHOLD` a esse ganho.
+pub fn trivial_first_move(
+    start:    &Chess,
+    blunder:  &Move,
+    first:    &Move,
+    net_gain: i32,
+    mate:     bool,
+) -> Option<TrivialKind> {
+    if mate || !first.is_capture() { return None; }
+
+    let gain = see(start.board(), first);
+    let kind = if blunder.is_capture() && first.to() == blunder.to() && gain >= 0 {
+        TrivialKind::Recapture
+    } else if first.capture().is_some_and(|c| gain >= piece_value(c)) {
+        TrivialKind::HangingPiece
+    } else {
+        return None;
+    };
+    (net_gain - gain < config::HANGING_THRESHOLD).then_some(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shakmaty::{fen::Fen, uci::UciMove, CastlingMode};
+
+    fn position(fen: &str) -> Chess {
+        fen.parse::<Fen>().unwrap().into_position(CastlingMode::Standard).unwrap()
+    }
+
+    fn uci(pos: &Chess, s: &str) -> Move {
+        s.parse::<UciMove>().unwrap().to_move(pos).unwrap()
+    }
+
+    #[test]
+    fn exchange_values() {
+        let cases = [
+            // peça pendurada
+            ("4k3/8/8/3n4/8/8/8/3RK3 w - - 0 1",          "d1d5",  300),
+            // peão defendido: o cavalo se perde
+            ("4k3/8/4p3/3p4/8/2N5/8/4K3 w - - 0 1",       "c3d5", -200),
+            // cavalo defendido pela torre: sem apoio a troca perde a qualidade...
+            ("3r2k1/8/8/3n4/8/8/8/3RK3 w - - 0 1",        "d1d5", -200),
+            // ...com a torre dobrada atrás (raio‑x) a recaptura final é nossa
+            ("3r2k1/8/8/3n4/8/8/3R4/3RK3 w - - 0 1",      "d2d5",  300),
+        ];
+        for (fen, mv, expected) in cases {
+            let pos = position(fen);
+            assert_eq!(see(pos.board(), &uci(&pos, mv)), expected, "{fen} {mv}");
+        }
+    }
+
+    /// `trivial_first_move` após o blunder `blunder` jogado em `fen`.
+    fn trivial(fen: &str, blunder: &str, first: &str, net_gain: i32, mate: bool) -> Option<TrivialKind> {
+        let pre  = position(fen);
+        let bl   = uci(&pre, blunder);
+        let post = pre.play(&bl).unwrap();
+        trivial_first_move(&post, &bl, &uci(&post, first), net_gain, mate)
+    }
+
+    #[test]
+    fn trivial_first_moves() {
+        // ...Nxe4 dxe4: recaptura simples
+        let recapture = "4k3/8/5n2/8/4P3/3P4/8/4K3 b - - 0 1";
+        assert_eq!(trivial(recapture, "f6e4", "d3e4", 300, false), Some(TrivialKind::Recapture));
+        // a linha ganha bem mais que a recaptura, ou termina em mate: não é trivial
+        assert_eq!(trivial(recapture, "f6e4", "d3e4", 800, false), None);
+        assert_eq!(trivial(recapture, "f6e4", "d3e4", 300, true), None);
+
+        // cavalo largado em d5
+        let hanging = "4k3/8/8/3n4/8/8/8/3RK3 b - - 0 1";
+        assert_eq!(trivial(hanging, "e8e7", "d1d5", 300, false), Some(TrivialKind::HangingPiece));
+
+        // captura de peça defendida não é trivial
+        let defended = "3r2k1/8/8/3n4/8/8/8/3RK3 b - - 0 1";
+        assert_eq!(trivial(defended, "g8g7", "d1d5", 300, false), None);
+    }
+}
@@ -25,6 +25,12 @@ pub struct PuzzleStatistics {
     // Estatísticas detalhadas
     pub objective_stats: HashMap<String, u64>,
     pub phase_stats: HashMap<String, u64>,
+    #[serde(default)]
+    pub kind_stats: HashMap<String, u64>,
+    #[serde(default)]
+    pub theme_stats: HashMap<String, u64>,
+    #[serde(default)]
+    pub opening_stats: HashMap<String, u64>,
     pub rejection_reasons: HashMap<String, u64>,
 }
 
@@ -79,6 +85,21 @@ impl PuzzleStatistics {
         *self.phase_stats.entry(phase.to_string()).or_insert(0) += count;
     }
 
+    // Separa blunders punidos de blunders não punidos (MissedWin)
+    pub fn update_kind(&mut self, kind: &str, count: u64) {
+        *self.kind_stats.entry(kind.to_string()).or_insert(0) += count;
+    }
+
+    // Motivos táticos (header Themes) para montar treinos por tema
+    pub fn update_theme(&mut self, theme: &str, count: u64) {
+        *self.theme_stats.entry(theme.to_string()).or_insert(0) += count;
+    }
+
+    // Família da abertura (header Opening) para treinos por repertório
+    pub fn update_opening(&mut self, family: &str, count: u64) {
+        *self.opening_stats.entry(family.to_string()).or_insert(0) += count;
+    }
+
     pub fn get_elapsed_time(&self) -> u64 {
         let current = self.start_time.map(|t| t.elapsed().as_secs()).unwrap_or(0);
         self.elapsed_secs + current
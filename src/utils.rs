@@ -14,12 +14,13 @@ use std::{
 use anyhow::{anyhow, Context, Result};
 use indexmap::IndexMap;
 use log::{trace, warn};
-use pgn_reader::{BufferedReader, RawHeader, SanPlus, Skip, Visitor};
+use pgn_reader::{BufferedReader, RawComment, RawHeader, SanPlus, Skip, Visitor};
 use shakmaty::{san::San, fen::Fen, CastlingMode, Chess, Color, Move, Position};
 
 use crate::{
-    builder::PuzzleSeq,
+    builder::{PuzzleSeq, Variation},
     config,
+    eco,
     engine::Engine,
 };
 
@@ -47,6 +48,46 @@ pub fn count_games(path: &Path) -> Result<u64> {
     Ok(c.n as u64)
 }
 
+// ---------------------------------------------------------------------------
+// Leitor de headers - usado para reprocessar PGNs de puzzles já exportados
+// ---------------------------------------------------------------------------
+struct HeaderCollector { hdr: IndexMap<String, String> }
+
+impl Visitor for HeaderCollector {
+    type Result = IndexMap<String, String>;
+    fn begin_game(&mut self) { self.hdr.clear(); }
+    fn header(&mut self, n: &[u8], v: RawHeader<'_>) {
+        if let (Ok(k), Ok(val)) = (std::str::from_utf8(n), std::str::from_utf8(v.as_bytes())) {
+            self.hdr.insert(k.into(), val.trim_matches('"').into());
+        }
+    }
+    fn end_headers(&mut self) -> Skip { Skip(true) }
+    fn begin_variation(&mut self) -> Skip { Skip(true) }
+    fn end_game(&mut self) -> Self::Result { std::mem::take(&mut self.hdr) }
+}
+
+/// Lê apenas os headers de cada jogo de um arquivo PGN
+pub fn read_pgn_headers(path: &Path) -> Result<Vec<IndexMap<String, String>>> {
+    let f = File::open(path).with_context(|| format!("abrir {}", path.display()))?;
+    let mut rdr = BufferedReader::new(BufReader::new(f));
+    let mut v = HeaderCollector { hdr: IndexMap::new() };
+    let mut out = Vec::new();
+    while let Some(h) = rdr.read_game(&mut v)? { out.push(h); }
+    Ok(out)
+}
+
+/// Extrai o relógio (em segundos) de um comentário `[%clk h:mm:ss]`
+pub fn parse_clock(comment: &str) -> Option<u32> {
+    let rest = &comment[comment.find("[%clk")? + 5..];
+    let value = rest[..rest.find(']')?].trim();
+    let mut secs = 0u32;
+    for part in value.split(':') {
+        let whole = part.split('.').next()?.trim();
+        secs = secs * 60 + whole.parse::<u32>().ok()?;
+    }
+    Some(secs)
+}
+
 // ---------------------------------------------------------------------------
 // MoveRecord & iterate_games - iteração preguiçosa sobre lances de uma partida
 // ---------------------------------------------------------------------------
@@ -54,12 +95,13 @@ pub fn count_games(path: &Path) -> Result<u64> {
 #[derive(Debug, Clone)]
 pub struct MoveRecord {
     pub game_idx: u32,                      // Índice do jogo no arquivo
-    pub move_idx: u32,                      // Número do lance no jogo
+    pub move_idx: u32,                      // Ply no jogo (1 = primeiro lance das brancas)
     pub side:     Color,                    // Cor que executa o lance
     pub board:    Chess,                    // Posição antes do lance
     pub san:      String,                   // Notação algébrica do lance
     pub mv:       Move,                     // Movimento em formato interno
     pub headers:  Vec<(String,String)>,     // Headers do PGN do jogo
+    pub clock:    Option<u32>,              // Relógio (s) de quem jogou, via [%clk]
 }
 
 /// Iterador preguiçoso de lances do PGN - processa um jogo por vez
@@ -81,18 +123,19 @@ pub fn iterate_games(path: &Path) -> Result<impl Iterator<Item=MoveRecord>> {
         hdr: Vec<(String,String)>,          // Headers coletados
         q  : &'a mut VecDeque<MoveRecord>,  // Referência para fila de saída
         gi : u32,                           // Índice do jogo
+        eco: Option<&'static eco::Opening>, // Última posição da partida na tabela ECO
     }
 
     impl<'a> V<'a> {
         fn new(q: &'a mut VecDeque<MoveRecord>, gi: u32) -> Self {
-            Self { b: Chess::default(), mi:0, hdr:Vec::new(), q, gi }
+            Self { b: Chess::default(), mi:0, hdr:Vec::new(), q, gi, eco:None }
         }
     }
 
     // Implementação do visitor para processar lances
     impl<'a> Visitor for V<'a> {
         type Result = ();
-        fn begin_game(&mut self){ self.b = Chess::default(); self.mi=0; self.hdr.clear(); }
+        fn begin_game(&mut self){ self.b = Chess::default(); self.mi=0; self.hdr.clear(); self.eco=None; }
 
         // Coleta headers do PGN
         fn header(&mut self,n:&[u8],v:RawHeader<'_>){
@@ -107,12 +150,36 @@ pub fn iterate_games(path: &Path) -> Result<impl Iterator<Item=MoveRecord>> {
                 self.mi+=1;
                 self.q.push_back(MoveRecord{
                     game_idx:self.gi, move_idx:self.mi, side:self.b.turn(),
-                    board:self.b.clone(), san:sp.san.to_string(), mv:mv.clone(), headers:self.hdr.clone()
+                    board:self.b.clone(), san:sp.san.to_string(), mv:mv.clone(), headers:self.hdr.clone(),
+                    clock:None
                 });
                 self.b.play_unchecked(&mv);
+                if self.mi <= config::ECO_MAX_PLIES { self.eco = eco::lookup(&self.b).or(self.eco); }
             }
         }
-        fn end_game(&mut self){}
+
+        // Relógio do lance anterior ([%clk h:mm:ss])
+        fn comment(&mut self, c:RawComment<'_>){
+            if let (Some(rec), Ok(txt)) = (self.q.back_mut(), std::str::from_utf8(c.as_bytes())) {
+                if rec.game_idx==self.gi && rec.move_idx==self.mi { rec.clock = parse_clock(txt).or(rec.clock); }
+            }
+        }
+
+        // Variantes não fazem parte da partida: não aplicá-las ao tabuleiro
+        fn begin_variation(&mut self)->Skip{ Skip(true) }
+
+        // ECO/Opening/Variation da tabela embutida quando a partida não os traz
+        fn end_game(&mut self){
+            let Some(op) = self.eco else { return };
+            let known = |k:&str| self.hdr.iter().any(|(n,v)| n==k && !v.is_empty() && v!="?");
+            let skip  = |k:&str| known(k) || (k=="Variation" && known("Opening")); // variante só com a nossa Opening
+            let tags: Vec<(String,String)> = [("ECO", Some(op.eco)), ("Opening", Some(op.family())), ("Variation", op.variation())]
+                .into_iter()
+                .filter_map(|(k,v)| Some((k.to_string(), v?.to_string())))
+                .filter(|(k,_)| !skip(k))
+                .collect();
+            for rec in self.q.iter_mut().filter(|r| r.game_idx==self.gi) { rec.headers.extend(tags.iter().cloned()); }
+        }
     }
 
     // Inicializa estado
@@ -205,6 +272,16 @@ pub fn prepare_output_file(input:&PathBuf, out:Option<&PathBuf>, resume:bool)->R
     Ok((path,f))
 }
 
+// ---------------------------------------------------------------------------
+// Replay - posição final de uma linha (mesma reprodução do `build_pgn_san`)
+// ---------------------------------------------------------------------------
+/// Reproduz `moves` a partir de `start` e devolve a posição final
+pub fn replay_line(start: &Chess, moves: &[Move]) -> Chess {
+    let mut board = start.clone();
+    for mv in moves { board.play_unchecked(mv); }
+    board
+}
+
 // ---------------------------------------------------------------------------
 // Build PGN - constrói representação PGN final do puzzle
 // ---------------------------------------------------------------------------
@@ -216,15 +293,13 @@ where
     K: AsRef<str>,
     V: AsRef<str>,
 {
-    use shakmaty::{san::San, fen::Fen, CastlingMode, Color};
-
     // cabeçalhos
     let mut pgn = String::new();
     for (k, v) in hdr { pgn.push_str(&format!("[{} \"{}\"]\n", k.as_ref(), v.as_ref())); }
     pgn.push('\n');
 
     // tabuleiro inicial
-    let mut board = if let Some(fen) =
+    let board = if let Some(fen) =
         hdr.iter()
            .find_map(|(k, v)| k.as_ref().eq_ignore_ascii_case("fen").then(|| v.as_ref()))
     {
@@ -233,33 +308,226 @@ where
         Chess::default()
     };
 
-    // numeração SAN   (1… Rxg3 2. hxg3)
-    let mut turn = board.turn();
-    let mut ply  = 0usize;
-    for mv in &seq.moves {
-        if turn == Color::White {
-            pgn.push_str(&format!("{}.", ply / 2 + 1));
-        } else if ply == 0 {
-            pgn.push_str(&format!("{}... ", ply / 2 + 1));
-        }
-        pgn.push_str(&format!("{} ", San::from_move(&board, mv)));
+    // lances, comentários e variantes (numeração pela FEN: 23... Rxg3 24.hxg3)
+    write_line(&mut pgn, &board, &seq.moves, &seq.comments, &seq.evals, &seq.glyphs, &seq.variations);
+
+    Ok(pgn.trim_end().into())
+}
+
+/// Linha numerada em SAN (ex.: "24.Qxd5? Rxd5"), sem comentários nem variantes.
+pub fn san_line(start: &Chess, moves: &[Move], glyphs: &[(usize, &str)]) -> String {
+    let mut out = String::new();
+    write_line(&mut out, start, moves, &[], &[], glyphs, &[]);
+    out.trim_end().into()
+}
+
+/// Lances em SAN, sem numeração, a partir de `start`.
+pub fn san_moves(start: &Chess, moves: &[Move]) -> Vec<String> {
+    let mut board = start.clone();
+    moves.iter().map(|mv| {
+        let san = San::from_move(&board, mv).to_string();
         board.play_unchecked(mv);
-        turn = board.turn();
-        ply += 1;
+        san
+    }).collect()
+}
+
+/// Avaliação no formato `[%eval 1.23]` / `[%eval #-3]` (cp das brancas).
+pub fn eval_tag(cp: i32) -> String {
+    if cp.abs() >= 90_000 {
+        format!("[%eval #{}{}]", if cp < 0 { "-" } else { "" }, 100_000 - cp.abs())
+    } else {
+        format!("[%eval {:.2}]", cp as f32 / 100.0)
     }
+}
 
-    // variantes (se houver)
-    let main_end = board.clone();
-    for var in &seq.alternatives {
-        pgn.push('(');
-        let mut b = main_end.clone();
-        for mv in var {
-            pgn.push_str(&format!("{} ", San::from_move(&b, mv)));
-            b.play_unchecked(mv);
+/// Escreve `moves` a partir de `start`, com sufixos, avaliações, comentários
+/// e as variantes (recursivas) logo após o lance que substituem.
+fn write_line(
+    pgn:        &mut String,
+    start:      &Chess,
+    moves:      &[Move],
+    comments:   &[(usize, String)],
+    evals:      &[Option<i32>],
+    glyphs:     &[(usize, &str)],
+    variations: &[Variation],
+) {
+    let mut board  = start.clone();
+    let mut number = true;                     // repetir "N..." antes de lance das pretas
+    for (ply, mv) in moves.iter().enumerate() {
+        match board.turn() {
+            Color::White           => pgn.push_str(&format!("{}.", board.fullmoves())),
+            Color::Black if number => pgn.push_str(&format!("{}... ", board.fullmoves())),
+            Color::Black           => {}
         }
-        if pgn.ends_with(' ') { pgn.pop(); }
-        pgn.push(')');
+        pgn.push_str(&San::from_move(&board, mv).to_string());
+        for (_, g) in glyphs.iter().filter(|(i, _)| *i == ply) { pgn.push_str(g); }
         pgn.push(' ');
+        number = false;
+
+        if let Some(Some(cp)) = evals.get(ply) {
+            pgn.push_str(&format!("{{ {} }} ", eval_tag(*cp)));
+            number = true;
+        }
+        for (_, c) in comments.iter().filter(|(i, _)| *i == ply) {
+            pgn.push_str(&format!("{{ {} }} ", c));
+            number = true;
+        }
+        for var in variations.iter().filter(|v| v.ply == ply) {
+            pgn.push('(');
+            write_line(pgn, &board, &var.moves, &[], &[], &[], &var.variations);
+            if pgn.ends_with(' ') { pgn.pop(); }
+            pgn.push_str(") ");
+            number = true;
+        }
+        board.play_unchecked(mv);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{PuzzleSeq, Variation};
+
+    /// Relê o PGN validando cada lance (inclusive nas variantes) a partir da
+    /// posição de onde ele ramifica.
+    #[derive(Default)]
+    struct Replay {
+        fen:        Option<String>,
+        pos:        Chess,
+        before:     Chess,                     // posição antes do último lance
+        stack:      Vec<(Chess, Chess)>,
+        illegal:    Vec<String>,
+        main:       Vec<String>,
+        sans:       usize,
+        variations: usize,
+    }
+
+    impl Visitor for Replay {
+        type Result = ();
+        fn header(&mut self, n: &[u8], v: RawHeader<'_>) {
+            if n == b"FEN" { self.fen = Some(v.decode_utf8_lossy().into_owned()); }
+        }
+        fn end_headers(&mut self) -> Skip {
+            self.pos = self.fen.as_deref().map_or_else(Chess::default, position);
+            self.before = self.pos.clone();
+            Skip(false)
+        }
+        fn san(&mut self, sp: SanPlus) {
+            match sp.san.to_move(&self.pos) {
+                Ok(mv) => {
+                    if self.stack.is_empty() { self.main.push(sp.san.to_string()); }
+                    self.before = self.pos.clone();
+                    self.pos.play_unchecked(&mv);
+                    self.sans += 1;
+                }
+                Err(_) => self.illegal.push(sp.san.to_string()),
+            }
+        }
+        fn begin_variation(&mut self) -> Skip {
+            self.stack.push((self.pos.clone(), self.before.clone()));
+            self.pos = self.before.clone();
+            self.variations += 1;
+            Skip(false)
+        }
+        fn end_variation(&mut self) {
+            if let Some((pos, before)) = self.stack.pop() { (self.pos, self.before) = (pos, before); }
+        }
+        fn end_game(&mut self) {}
+    }
+
+    fn position(fen: &str) -> Chess {
+        fen.parse::<Fen>().unwrap().into_position(CastlingMode::Standard).unwrap()
+    }
+
+    fn line(start: &Chess, sans: &[&str]) -> Vec<Move> {
+        let mut b = start.clone();
+        sans.iter()
+            .map(|s| {
+                let mv = s.parse::<San>().unwrap().to_move(&b).unwrap();
+                b.play_unchecked(&mv);
+                mv
+            })
+            .collect()
+    }
+
+    fn after(start: &Chess, sans: &[&str]) -> Chess { replay_line(start, &line(start, sans)) }
+
+    fn seq(moves: Vec<Move>, variations: Vec<Variation>, comments: Vec<(usize, String)>) -> PuzzleSeq {
+        PuzzleSeq {
+            moves, unique: Vec::new(), variations, final_cp: 0, is_mate: false, comments,
+            evals: Vec::new(), glyphs: Vec::new(), refutations: Vec::new(), draw: None,
+        }
+    }
+
+    fn round_trip(fen: &str, s: &PuzzleSeq) -> (String, Replay) {
+        let hdr: IndexMap<String, String> = [("FEN".to_string(), fen.to_string())].into_iter().collect();
+        let pgn = build_pgn_san(&hdr, s).unwrap();
+        let mut replay = Replay::default();
+        BufferedReader::new_cursor(pgn.as_bytes()).read_game(&mut replay).unwrap();
+        (pgn, replay)
+    }
+
+    #[test]
+    fn variations_are_legal_from_branch_point_white_to_move() {
+        let fen   = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3";
+        let start = position(fen);
+        let main  = line(&start, &["Bb5", "a6", "Ba4", "Nf6", "O-O"]);
+        let variations = vec![
+            // alternativa do resolvedor no 1º lance
+            Variation { ply: 0, moves: line(&start, &["Bc4", "Nf6", "d3"]), variations: vec![] },
+            // defesa alternativa do adversário, com sub‑variante do resolvedor
+            Variation {
+                ply: 1,
+                moves: line(&after(&start, &["Bb5"]), &["Nf6", "O-O"]),
+                variations: vec![Variation {
+                    ply: 1,
+                    moves: line(&after(&start, &["Bb5", "Nf6"]), &["d3"]),
+                    variations: vec![],
+                }],
+            },
+        ];
+        let s = seq(main, variations, vec![(0, "chave".into())]);
+
+        let (pgn, r) = round_trip(fen, &s);
+        assert!(r.illegal.is_empty(), "{pgn}: {:?}", r.illegal);
+        assert_eq!(r.main, ["Bb5", "a6", "Ba4", "Nf6", "O-O"], "{pgn}");
+        assert_eq!(r.variations, 3, "{pgn}");
+        assert_eq!(r.sans, 5 + 3 + 2 + 1, "{pgn}");
+        assert!(pgn.ends_with("3.Bb5 { chave } (3.Bc4 Nf6 4.d3) 3... a6 (3... Nf6 4.O-O (4.d3)) 4.Ba4 Nf6 5.O-O"), "{pgn}");
+    }
+
+    #[test]
+    fn variations_are_legal_from_branch_point_black_to_move() {
+        let fen   = "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2";
+        let start = position(fen);
+        let main  = line(&start, &["Nc6", "Bb5", "a6", "Ba4", "Nf6"]);
+        let variations = vec![
+            Variation { ply: 0, moves: line(&start, &["d6", "d4", "exd4"]), variations: vec![] },
+            Variation { ply: 2, moves: line(&after(&start, &["Nc6", "Bb5"]), &["Nf6", "O-O", "Nxe4"]), variations: vec![] },
+            Variation { ply: 3, moves: line(&after(&start, &["Nc6", "Bb5", "a6"]), &["Bxc6", "dxc6"]), variations: vec![] },
+        ];
+        let s = seq(main, variations, vec![(1, "ameaça".into())]);
+
+        let (pgn, r) = round_trip(fen, &s);
+        assert!(r.illegal.is_empty(), "{pgn}: {:?}", r.illegal);
+        assert_eq!(r.main.len(), 5, "{pgn}");
+        assert_eq!(r.variations, 3, "{pgn}");
+        assert_eq!(r.sans, 5 + 3 + 3 + 2, "{pgn}");
+        assert!(pgn.ends_with("2... Nc6 (2... d6 3.d4 exd4) 3.Bb5 { ameaça } 3... a6 (3... Nf6 4.O-O Nxe4) 4.Ba4 (4.Bxc6 dxc6) 4... Nf6"), "{pgn}");
+    }
+
+    #[test]
+    fn annotations_round_trip() {
+        let fen   = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3";
+        let start = position(fen);
+        let mut s = seq(line(&start, &["Bb5", "a6", "Ba4"]), vec![], vec![]);
+        s.evals  = vec![Some(25), None, Some(99_997)];
+        s.glyphs = vec![(0, "!"), (1, "??")];
+
+        let (pgn, r) = round_trip(fen, &s);
+        assert!(r.illegal.is_empty(), "{pgn}: {:?}", r.illegal);
+        assert_eq!(r.main.len(), 3, "{pgn}");
+        assert!(pgn.ends_with("3.Bb5! { [%eval 0.25] } 3... a6?? 4.Ba4 { [%eval #3] }"), "{pgn}");
+        assert_eq!(san_line(&start, &line(&start, &["Bb5", "a6"]), &[(0, "?")]), "3.Bb5? a6");
     }
-    Ok(pgn.trim_end().into())
 }
@@ -218,3 +218,27 @@ pub fn render_end_statistics(
 
     Ok(())
 }
+
+// Relatório de fraquezas do jogador (subcomando `report`)
+pub fn render_weakness_report(report: &crate::report::WeaknessReport, json_path: &Path) -> Result<()> {
+    println!("{}", format!("Relatório de fraquezas — {}", report.player).cyan().bold());
+    println!("- Puzzles considerados: {}", report.stats.puzzles_found);
+
+    if !report.role_stats.is_empty() {
+        println!("- Papel do jogador:");
+        for (role, count) in &report.role_stats {
+            println!("  - {}: {}", role, count);
+        }
+    }
+
+    let summary = report.summary();
+    if !summary.is_empty() {
+        println!("- Principais fraquezas:");
+        for line in summary {
+            println!("  - {}", line);
+        }
+    }
+
+    println!("\nRelatório JSON salvo em: {}", json_path.display());
+    Ok(())
+}